@@ -0,0 +1,97 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Directory path of the repository
+    #[arg(long)]
+    basepath: String,
+    /// Rewrite files instead of printing the diff
+    #[arg(long, default_value_t = false)]
+    write: bool,
+    /// Encoding of source files which are not UTF-8 (e.g. shift_jis); files are rewritten in it
+    #[arg(long)]
+    fallback_encoding: Option<String>,
+    /// Locale of title, overview and usage of articles (default: ja); sections without suffix such as %title are ja
+    #[arg(long)]
+    default_locale: Option<String>,
+}
+
+// Formats DOC blocks of source files. Without --write, prints the diff and
+// exits with 1 if any file is not formatted.
+fn main() {
+    let args = Args::parse();
+
+    let base_path = args.basepath;
+
+    if !std::path::Path::new(&base_path).is_dir() {
+        eprintln!("{} is not a directory", base_path);
+        std::process::exit(1);
+    }
+
+    let collection = match codelib2_tools::gather_collection(
+        &base_path,
+        codelib2_tools::CollectionOptions {
+            fallback_encoding: args.fallback_encoding,
+            default_locale: args.default_locale,
+            ..Default::default()
+        },
+    ) {
+        Ok(collection) => collection,
+        Err(err) => {
+            eprintln!("Failed: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let mut failed = false;
+    for source_sets in &collection.source_sets {
+        for src_path in &source_sets.src_paths {
+            let total_path = collection.complete_path_str(src_path);
+            let fallback_encoding = collection.fallback_encoding.as_deref();
+            let formatted = std::fs::read(&total_path)
+                .map_err(|err| err.to_string())
+                .and_then(|bytes| {
+                    // the same text as gather reads
+                    let text = codelib2_tools::decode_source(&bytes, fallback_encoding)
+                        .map_err(|err| err.with_path(src_path).to_string())?;
+                    codelib2_tools::format_document_from_str(&text, &source_sets.lang)
+                        .map(|formatted| (bytes, text, formatted))
+                        .map_err(|err| err.with_path(src_path).to_string())
+                });
+            let (bytes, text, formatted) = match formatted {
+                Ok(formatted) => formatted,
+                Err(err) => {
+                    eprintln!("Failed to format: {}\n{}", src_path, err);
+                    failed = true;
+                    continue;
+                }
+            };
+            if formatted == text {
+                continue;
+            }
+            if args.write {
+                // keep encoding, BOM and line endings of the original bytes
+                let written = codelib2_tools::encode_source(&formatted, &bytes, fallback_encoding)
+                    .map_err(|err| err.with_path(src_path).to_string())
+                    .and_then(|encoded| {
+                        std::fs::write(&total_path, encoded).map_err(|err| err.to_string())
+                    });
+                if let Err(err) = written {
+                    eprintln!("Failed to write: {}\n{}", src_path, err);
+                    failed = true;
+                }
+            } else {
+                print!(
+                    "{}",
+                    codelib2_tools::diff_lines(src_path, &text, &formatted)
+                );
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
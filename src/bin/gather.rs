@@ -15,6 +15,27 @@ struct Args {
     /// Output collection json file
     #[arg(long)]
     output_path_collection: Option<String>,
+    /// Output problem index json file (judge problem -> articles)
+    #[arg(long)]
+    output_path_problem_index: Option<String>,
+    /// Exclude deprecated articles from outputs
+    #[arg(long, default_value_t = false)]
+    exclude_deprecated: bool,
+    /// Output directory of %example programs for the test runner
+    #[arg(long)]
+    output_dir_example: Option<String>,
+    /// Encoding of source files which are not UTF-8 (e.g. shift_jis)
+    #[arg(long)]
+    fallback_encoding: Option<String>,
+    /// Locale of title, overview and usage of articles (default: ja); sections without suffix such as %title are ja
+    #[arg(long)]
+    default_locale: Option<String>,
+    /// Directory searched for includes after the repository root (e.g. /src/cpp); repeatable
+    #[arg(long = "include-root")]
+    include_roots: Vec<String>,
+    /// Prefix of includes of this library (e.g. codelib for <codelib/graph/dijkstra.hpp>)
+    #[arg(long)]
+    library_prefix: Option<String>,
 }
 
 fn main() {
@@ -36,12 +57,28 @@ fn main() {
         None
     };
 
+    let writer_problem_index =
+        if let Some(output_path_problem_index) = args.output_path_problem_index {
+            let writer = std::fs::File::create(output_path_problem_index).unwrap();
+            Some(writer)
+        } else {
+            None
+        };
+
     if !std::path::Path::new(&base_path).is_dir() {
         eprintln!("{} is not a directory", base_path);
         std::process::exit(1);
     }
 
-    let collection = match codelib2_tools::gather_collection(&base_path) {
+    let collection = match codelib2_tools::gather_collection(
+        &base_path,
+        codelib2_tools::CollectionOptions {
+            fallback_encoding: args.fallback_encoding,
+            default_locale: args.default_locale,
+            include_roots: args.include_roots,
+            library_prefix: args.library_prefix,
+        },
+    ) {
         Ok(collection) => collection,
         Err(err) => {
             eprintln!("Failed: {}", err);
@@ -57,13 +94,19 @@ fn main() {
         };
     }
 
-    let articles = match codelib2_tools::complete_articles(&collection) {
-        Ok(articles) => articles,
-        Err(err) => {
-            eprintln!("Failed: {}", err);
-            std::process::exit(1);
-        }
-    };
+    let code_info_sets = codelib2_tools::collect_code_infos(&collection);
+    let mut articles =
+        match codelib2_tools::complete_articles_with_code_infos(&collection, &code_info_sets) {
+            Ok(articles) => articles,
+            Err(err) => {
+                eprintln!("Failed: {}", err);
+                std::process::exit(1);
+            }
+        };
+
+    if args.exclude_deprecated {
+        articles.retain(|article| article.status != codelib2_tools::ArticleStatus::Deprecated);
+    }
 
     if let Some(writer_article) = writer_article {
         if args.pretty {
@@ -72,4 +115,21 @@ fn main() {
             serde_json::to_writer(writer_article, &articles).unwrap();
         };
     }
+
+    if let Some(output_dir_example) = args.output_dir_example {
+        if let Err(err) = codelib2_tools::write_examples(&articles, &output_dir_example) {
+            eprintln!("Failed: {}", err);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(writer_problem_index) = writer_problem_index {
+        let problem_index =
+            codelib2_tools::complete_problem_index(&collection, &code_info_sets, &articles);
+        if args.pretty {
+            serde_json::to_writer_pretty(writer_problem_index, &problem_index).unwrap();
+        } else {
+            serde_json::to_writer(writer_problem_index, &problem_index).unwrap();
+        };
+    }
 }
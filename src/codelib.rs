@@ -1,30 +1,166 @@
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Commit {
     pub sha: String,
     pub date: String,
     pub message: String,
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+// A signature line in %usage and following `;` lines describing it.
+#[derive(Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct UsageEntry {
+    pub signature: String,
+    pub description: String,
+}
+
+// A line of %complexity. Big-O expressions are normalised, e.g. `O(N log N)`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct ComplexityEntry {
+    pub operation: String,
+    pub time: String,
+    pub space: Option<String>,
+}
+
+// 1-based, inclusive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct LineRange {
+    pub begin: usize,
+    pub end: usize,
+}
+
+// A piece of code surrounded by `%=BEGIN CODE <name>` and `%=END CODE`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct CodeBlock {
+    pub name: Option<String>,
+    pub code: String,
+    pub line_range: LineRange, // lines between the anchors
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+pub enum Judge {
+    #[serde(rename = "yukicoder")]
+    Yukicoder,
+    #[serde(rename = "atcoder")]
+    AtCoder,
+    #[serde(rename = "aoj")]
+    Aoj,
+    #[serde(rename = "codeforces")]
+    Codeforces,
+    #[serde(rename = "library_checker")]
+    LibraryChecker,
+}
+
+// A URL in %verified, classified by its shape.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct VerifiedEntry {
+    pub url: String,
+    pub judge: Option<Judge>, // None if unknown
+    pub problem_id: Option<String>,
+    pub submission_id: Option<String>,
+}
+
+// %status; `%deprecated` also makes an article deprecated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleStatus {
+    Experimental,
+    #[default]
+    Stable,
+    Deprecated,
+}
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
 pub struct Article {
+    pub id: String, // path, or path#name if the file has several articles
     pub title: String,
     pub overview: String,
+    pub usage: String,
+    pub usage_entries: Vec<UsageEntry>,
+    // per locale, including the default locale of the flat fields above
+    pub localized_title: BTreeMap<String, String>,
+    pub localized_overview: BTreeMap<String, String>,
+    pub localized_usage: BTreeMap<String, String>,
+    pub complexity: Vec<ComplexityEntry>,
     pub code: String,
+    pub code_blocks: Vec<CodeBlock>,
+    pub code_line_range: LineRange, // from the first code block to the last one
     pub lang: String,
     pub path: String,
     pub require: String,
+    pub example: String, // a program using `require` and `code`
     pub references: Vec<String>,
     pub words: Vec<String>,
+    pub status: ArticleStatus,
+    pub replacement: Option<String>, // article id or path in %deprecated
     pub verified: Vec<String>,
+    pub verified_entries: Vec<VerifiedEntry>,
+    pub extra: BTreeMap<String, String>, // unknown sections e.g. %foo
+    pub doc_line_range: LineRange,       // including the anchors
+    pub section_line_ranges: BTreeMap<String, LineRange>,
     pub commits: Vec<Commit>,
     pub tested_by: Vec<String>,
+    // tests reaching this only through includes in `#ifdef` and so on
+    pub conditionally_tested_by: Vec<String>,
+}
+
+// A judge problem and articles verified against it.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct ProblemIndexEntry {
+    pub judge: Judge,
+    pub problem_id: String,
+    pub urls: Vec<String>,
+    pub articles: Vec<String>,   // article ids
+    pub test_files: Vec<String>, // test files referencing the problem
 }
 
 // ------------------------------------
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncludeKind {
+    Quote,        // `#include "..."`, `#[path = "..."]`, `include!("...")` and `require_relative`
+    Angle,        // `#include <...>` and `require`
+    RustMod,      // `mod foo;`, written as `foo`
+    RustUse,      // `use crate::a::b;`, written as `crate::a::b`
+    PythonImport, // `import a.b` or `from .a import b`, written as `a.b` or `.a.b`
+}
+
+// Which candidate an include is resolved by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IncludeOrigin {
+    IncludingDirectory,
+    Root(String),
+    CrateRoot(String), // directory of lib.rs or main.rs
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedInclude {
+    pub path: String, // path in the collection
+    pub origin: IncludeOrigin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludeEntry {
+    pub include: String, // as written, e.g. `../util.hpp`
+    pub kind: IncludeKind,
+    pub conditional: bool, // in `#ifdef` and so on
+    pub resolved: Option<ResolvedInclude>,
+}
+
+#[derive(Debug, Default)]
 pub struct CodeInfo {
     pub filepath_dependencies: Vec<String>,
+    // reached only if some macros are (not) defined
+    pub conditional_dependencies: Vec<String>,
+    pub includes: Vec<IncludeEntry>,
+    // `<...>` includes and `require` out of the collection, e.g. `vector`
+    pub system_headers: Vec<String>,
+    // markers of online-judge-verify-helper in test files
+    pub problem: Option<String>, // #define PROBLEM "url"
+    pub ignore: bool,            // #define IGNORE
+    pub error: Option<String>,   // #define ERROR "1e-6"
 }
 
 pub struct CodeInfoSets {
@@ -47,6 +183,29 @@ pub struct SourceSets {
 pub struct Collection {
     pub base_path: String,
     pub source_sets: Vec<SourceSets>,
+    // Encoding label (e.g. "shift_jis") used for files which are not UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_encoding: Option<String>,
+    // Locale of title, overview and usage of articles; "ja" if None.
+    // Sections without suffix are always "ja".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_locale: Option<String>,
+    // Directories searched for includes after the repository root, e.g. "/src/cpp"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_roots: Vec<String>,
+    // First component of includes of this library, stripped before searching
+    // include roots, e.g. "codelib" for `#include <codelib/graph/dijkstra.hpp>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library_prefix: Option<String>,
+}
+
+// Settings of a Collection which are not collected from the repository.
+#[derive(Debug, Clone, Default)]
+pub struct CollectionOptions {
+    pub fallback_encoding: Option<String>,
+    pub default_locale: Option<String>,
+    pub include_roots: Vec<String>,
+    pub library_prefix: Option<String>,
 }
 
 impl Collection {
@@ -0,0 +1,72 @@
+// Export of %example as standalone programs, which are compiled and run by
+// the test runner so that examples in documents keep working.
+
+use std::path::Path;
+
+use crate::Article;
+
+#[cfg(test)]
+mod unittest;
+
+// `/src/cpp/a/x.hpp` -> `src/cpp/a/x.example.cpp`,
+// `/src/cpp/a/x.hpp#min` -> `src/cpp/a/x.min.example.cpp`
+pub fn example_path(article: &Article) -> String {
+    let path = article.path.trim_start_matches('/');
+    let (stem, ext) = match path.rsplit_once('.') {
+        Some((stem, ext)) if !ext.contains('/') => (stem, ext),
+        _ => (path, ""),
+    };
+    // a header is not a translation unit
+    let ext = match (article.lang.as_str(), ext) {
+        ("cpp", "h" | "hh" | "hpp" | "hxx" | "") => "cpp",
+        (_, ext) => ext,
+    };
+    let name = match article.id.split_once('#') {
+        Some((_, name)) => format!(".{}", name),
+        None => String::new(),
+    };
+    if ext.is_empty() {
+        format!("{}{}.example", stem, name)
+    } else {
+        format!("{}{}.example.{}", stem, name, ext)
+    }
+}
+
+// `require`, `code` and `example` in this order, or None without %example.
+pub fn example_source(article: &Article) -> Option<String> {
+    if article.example.is_empty() {
+        return None;
+    }
+    let mut source = String::new();
+    for part in [&article.require, &article.code, &article.example] {
+        if part.is_empty() {
+            continue;
+        }
+        if !source.is_empty() {
+            source.push('\n');
+        }
+        source.push_str(part);
+        source.push('\n');
+    }
+    Some(source)
+}
+
+// Writes examples under `output_dir` and returns written paths.
+pub fn write_examples(articles: &[Article], output_dir: &str) -> Result<Vec<String>, String> {
+    let mut written = Vec::new();
+    for article in articles {
+        let source = match example_source(article) {
+            Some(source) => source,
+            None => continue,
+        };
+        let path = Path::new(output_dir).join(example_path(article));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create {}: {}", parent.display(), err))?;
+        }
+        std::fs::write(&path, source)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+        written.push(path.to_string_lossy().into_owned());
+    }
+    Ok(written)
+}
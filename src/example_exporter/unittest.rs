@@ -0,0 +1,72 @@
+use crate::codelib::Article;
+#[cfg(test)]
+use crate::test_util::TempDir;
+
+#[cfg(test)]
+use super::{example_path, example_source, write_examples};
+
+fn article(id: &str, lang: &str, example: &str) -> Article {
+    Article {
+        id: id.to_string(),
+        code: "int gcd(int a, int b) { return b == 0 ? a : gcd(b, a % b); }".to_string(),
+        lang: lang.to_string(),
+        path: id.split('#').next().unwrap().to_string(),
+        require: "#include <cassert>".to_string(),
+        example: example.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_example_path() {
+    assert_eq!(
+        example_path(&article("/src/cpp/math/gcd.hpp", "cpp", "")),
+        "src/cpp/math/gcd.example.cpp"
+    );
+    assert_eq!(
+        example_path(&article("/src/cpp/math/gcd.hpp#lcm", "cpp", "")),
+        "src/cpp/math/gcd.lcm.example.cpp"
+    );
+    assert_eq!(
+        example_path(&article("/src/ruby/math/gcd.rb", "ruby", "")),
+        "src/ruby/math/gcd.example.rb"
+    );
+}
+
+#[test]
+fn test_example_source() {
+    assert_eq!(
+        example_source(&article("/src/cpp/math/gcd.hpp", "cpp", "")),
+        None
+    );
+    assert_eq!(
+        example_source(&article(
+            "/src/cpp/math/gcd.hpp",
+            "cpp",
+            "int main() { assert(gcd(12, 18) == 6); }"
+        ))
+        .unwrap(),
+        "#include <cassert>
+
+int gcd(int a, int b) { return b == 0 ? a : gcd(b, a % b); }
+
+int main() { assert(gcd(12, 18) == 6); }
+"
+    );
+}
+
+#[test]
+fn test_write_examples() {
+    let dir = TempDir::new("examples");
+    let output_dir = dir.path_str();
+    let articles = vec![
+        article("/src/cpp/math/gcd.hpp", "cpp", "int main() {}"),
+        article("/src/cpp/math/lcm.hpp", "cpp", ""),
+    ];
+    let written = write_examples(&articles, output_dir).unwrap();
+    let expected = format!("{}/src/cpp/math/gcd.example.cpp", output_dir);
+    assert_eq!(written, vec![expected.clone()]);
+    assert!(std::fs::read_to_string(&expected)
+        .unwrap()
+        .ends_with("int main() {}\n"));
+}
@@ -0,0 +1,300 @@
+// Resolution of includes to paths in the collection. Candidates are tried in
+// order: the directory of the including file (only for `#include "..."`),
+// then include roots. The library prefix, if any, is stripped from includes
+// before searching include roots. Unresolved `#include <...>` are system
+// headers.
+//
+// Rust modules are searched as rustc does: `mod foo;` in `a/lib.rs` or
+// `a/mod.rs` is `a/foo.rs` or `a/foo/mod.rs`, and in `a/b.rs` it is
+// `a/b/foo.rs` or `a/b/foo/mod.rs`. `use crate::x::y` is searched from the
+// nearest directory with lib.rs or main.rs, and `use <library prefix>::x::y`
+// from include roots. The longest module path found wins, since `y` may be an
+// item of `x.rs`.
+//
+// Python modules `a.b` are `a/b.py` or the package `a/b/__init__.py`. Relative
+// imports (`.a.b`) are searched from the directory of the importing file, one
+// level up per extra dot, and absolute ones from include roots. `require` and
+// `require_relative` of ruby are resolved as `<...>` and `"..."`.
+
+use std::collections::BTreeSet;
+
+use crate::codelib::{IncludeKind, IncludeOrigin, ResolvedInclude};
+use crate::{CodeInfo, Collection};
+
+#[cfg(test)]
+mod unittest;
+
+// The repository root, as the test runner compiles with `-I basedir`.
+pub const DEFAULT_INCLUDE_ROOT: &str = "/";
+
+// Collapses `.` and `..` of a path from the repository root.
+// None if it goes above the root.
+pub fn normalize_path(path: &str) -> Option<String> {
+    let mut components: Vec<&str> = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            _ => components.push(component),
+        }
+    }
+    Some(format!("/{}", components.join("/")))
+}
+
+// `/src/cpp/a/x.hpp` -> `/src/cpp/a`
+fn parent_directory(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(pos) => &path[..pos],
+        None => "",
+    }
+}
+
+// Directory of submodules of a rust file.
+// `/src/rust/a/mod.rs` -> `/src/rust/a`, `/src/rust/a/b.rs` -> `/src/rust/a/b`
+fn rust_module_directory(path: &str) -> String {
+    let directory = parent_directory(path);
+    let file_name = path[directory.len()..].trim_start_matches('/');
+    match file_name.strip_suffix(".rs") {
+        Some("mod" | "lib" | "main") | None => directory.to_string(),
+        Some(stem) => format!("{}/{}", directory, stem),
+    }
+}
+
+// `x/y` -> [`x/y.py`, `x/y/__init__.py`]; `` -> [`__init__.py`]
+fn python_module_files(directory: &str, module_path: &str) -> Vec<String> {
+    if module_path.is_empty() {
+        vec![format!("{}/__init__.py", directory)]
+    } else {
+        vec![
+            format!("{}/{}.py", directory, module_path),
+            format!("{}/{}/__init__.py", directory, module_path),
+        ]
+    }
+}
+
+// `x/y` -> [`x/y.rs`, `x/y/mod.rs`]
+fn rust_module_files(module_path: &str) -> [String; 2] {
+    [
+        format!("{}.rs", module_path),
+        format!("{}/mod.rs", module_path),
+    ]
+}
+
+pub struct IncludeResolver {
+    roots: Vec<String>,
+    library_prefix: Option<String>,
+    known_paths: BTreeSet<String>,
+}
+
+impl IncludeResolver {
+    pub fn new(
+        roots: Vec<String>,
+        library_prefix: Option<String>,
+        known_paths: BTreeSet<String>,
+    ) -> Self {
+        Self {
+            roots,
+            library_prefix,
+            known_paths,
+        }
+    }
+
+    // Searches the repository root and then the include roots of the
+    // collection. Knows all source and test paths of the collection.
+    pub fn from_collection(collection: &Collection) -> Self {
+        let roots = std::iter::once(DEFAULT_INCLUDE_ROOT.to_string())
+            .chain(collection.include_roots.iter().cloned())
+            .collect();
+        let known_paths = collection
+            .source_sets
+            .iter()
+            .flat_map(|source_sets| source_sets.src_paths.iter().chain(&source_sets.test_paths))
+            .cloned()
+            .collect();
+        Self::new(roots, collection.library_prefix.clone(), known_paths)
+    }
+
+    // `codelib/graph/dijkstra.hpp` -> `graph/dijkstra.hpp` if the prefix is
+    // `codelib` and the separator is `/`
+    fn strip_library_prefix<'a>(&self, include: &'a str, separator: char) -> &'a str {
+        self.library_prefix
+            .as_deref()
+            .and_then(|prefix| include.strip_prefix(prefix.trim_matches('/')))
+            .and_then(|rest| rest.strip_prefix(separator))
+            .unwrap_or(include)
+    }
+
+    // The nearest ancestor directory with lib.rs or main.rs.
+    fn rust_crate_root(&self, including_path: &str) -> Option<String> {
+        let mut directory = parent_directory(including_path);
+        loop {
+            let has_root_file = ["lib.rs", "main.rs"].iter().any(|file| {
+                self.known_paths
+                    .contains(&format!("{}/{}", directory, file))
+            });
+            if has_root_file {
+                return Some(directory.to_string());
+            }
+            if directory.is_empty() {
+                return None;
+            }
+            directory = parent_directory(directory);
+        }
+    }
+
+    fn root_candidates(&self, include: &str) -> Vec<(String, IncludeOrigin)> {
+        self.roots
+            .iter()
+            .map(|root| {
+                (
+                    format!("{}/{}", root.trim_end_matches('/'), include),
+                    IncludeOrigin::Root(root.clone()),
+                )
+            })
+            .collect()
+    }
+
+    // `crate::x::y` -> `x/y.rs`, `x/y/mod.rs`, `x.rs` and `x/mod.rs` under
+    // the crate root or include roots
+    fn rust_use_candidates(
+        &self,
+        including_path: &str,
+        include: &str,
+    ) -> Vec<(String, IncludeOrigin)> {
+        let mut segments = include.split("::");
+        let first = segments.next().unwrap_or_default();
+        let segments: Vec<&str> = segments.collect();
+        let bases = if first == "crate" {
+            match self.rust_crate_root(including_path) {
+                Some(crate_root) => {
+                    vec![(crate_root.clone(), IncludeOrigin::CrateRoot(crate_root))]
+                }
+                None => Vec::new(),
+            }
+        } else if self.library_prefix.as_deref() == Some(first) {
+            self.roots
+                .iter()
+                .map(|root| {
+                    (
+                        root.trim_end_matches('/').to_string(),
+                        IncludeOrigin::Root(root.clone()),
+                    )
+                })
+                .collect()
+        } else {
+            // std or other crates
+            Vec::new()
+        };
+        (1..=segments.len())
+            .rev()
+            .flat_map(|len| {
+                let module_path = segments[..len].join("/");
+                bases.iter().flat_map(move |(base, origin)| {
+                    rust_module_files(&format!("{}/{}", base, module_path))
+                        .map(|file| (file, origin.clone()))
+                })
+            })
+            .collect()
+    }
+
+    // `..a.b` -> `a/b.py` and `a/b/__init__.py` in the parent directory of the
+    // importing file
+    fn python_import_candidates(
+        &self,
+        including_path: &str,
+        include: &str,
+    ) -> Vec<(String, IncludeOrigin)> {
+        let module = include.trim_start_matches('.');
+        let level = include.len() - module.len();
+        if level == 0 {
+            let module_path = self.strip_library_prefix(module, '.').replace('.', "/");
+            return self
+                .roots
+                .iter()
+                .flat_map(|root| {
+                    python_module_files(root.trim_end_matches('/'), &module_path)
+                        .into_iter()
+                        .map(|file| (file, IncludeOrigin::Root(root.clone())))
+                })
+                .collect();
+        }
+        let mut directory = parent_directory(including_path);
+        for _ in 1..level {
+            directory = parent_directory(directory);
+        }
+        python_module_files(directory, &module.replace('.', "/"))
+            .into_iter()
+            .map(|file| (file, IncludeOrigin::IncludingDirectory))
+            .collect()
+    }
+
+    pub fn resolve(
+        &self,
+        including_path: &str,
+        include: &str,
+        kind: IncludeKind,
+    ) -> Option<ResolvedInclude> {
+        // an absolute path is out of the repository
+        if include.starts_with('/') {
+            return None;
+        }
+        let directory = parent_directory(including_path);
+        let candidates = match kind {
+            IncludeKind::Quote => {
+                let mut candidates = vec![(
+                    format!("{}/{}", directory, include),
+                    IncludeOrigin::IncludingDirectory,
+                )];
+                candidates.extend(self.root_candidates(self.strip_library_prefix(include, '/')));
+                candidates
+            }
+            IncludeKind::Angle => self.root_candidates(self.strip_library_prefix(include, '/')),
+            IncludeKind::RustMod => rust_module_files(&format!(
+                "{}/{}",
+                rust_module_directory(including_path),
+                include
+            ))
+            .map(|file| (file, IncludeOrigin::IncludingDirectory))
+            .to_vec(),
+            IncludeKind::RustUse => self.rust_use_candidates(including_path, include),
+            IncludeKind::PythonImport => self.python_import_candidates(including_path, include),
+        };
+        for (candidate, origin) in candidates {
+            if let Some(path) = normalize_path(&candidate) {
+                if self.known_paths.contains(&path) {
+                    return Some(ResolvedInclude { path, origin });
+                }
+            }
+        }
+        None
+    }
+
+    // Fills `resolved` of includes and replaces `filepath_dependencies` and
+    // `conditional_dependencies` with the resolved paths. Unresolved includes
+    // are kept in `includes`, and unresolved `<...>` are also listed in
+    // `system_headers`.
+    pub fn resolve_code_info(&self, including_path: &str, code_info: &mut CodeInfo) {
+        for include in &mut code_info.includes {
+            include.resolved = self.resolve(including_path, &include.include, include.kind);
+        }
+        code_info.system_headers = code_info
+            .includes
+            .iter()
+            .filter(|include| include.kind == IncludeKind::Angle && include.resolved.is_none())
+            .map(|include| include.include.clone())
+            .collect();
+        let resolved_paths = |conditional: bool| {
+            code_info
+                .includes
+                .iter()
+                .filter(|include| include.conditional == conditional)
+                .filter_map(|include| include.resolved.as_ref())
+                .map(|resolved| resolved.path.clone())
+                .collect()
+        };
+        code_info.filepath_dependencies = resolved_paths(false);
+        code_info.conditional_dependencies = resolved_paths(true);
+    }
+}
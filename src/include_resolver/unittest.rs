@@ -0,0 +1,373 @@
+use crate::codelib::{IncludeKind, IncludeOrigin, ResolvedInclude};
+use crate::parser::parse_code_info_from_str;
+
+#[cfg(test)]
+use super::{normalize_path, IncludeResolver};
+
+fn resolver(roots: &[&str]) -> IncludeResolver {
+    resolver_with_prefix(roots, None)
+}
+
+fn resolver_with_prefix(roots: &[&str], library_prefix: Option<&str>) -> IncludeResolver {
+    IncludeResolver::new(
+        roots.iter().map(|s| s.to_string()).collect(),
+        library_prefix.map(|s| s.to_string()),
+        [
+            "/src/cpp/graph/util.hpp",
+            "/src/cpp/graph/tree/node.hpp",
+            "/src/cpp/graph/tree/hld.hpp",
+            "/src/cpp/math/gcd.hpp",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect(),
+    )
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(
+        normalize_path("/src/cpp/graph/tree/../util.hpp"),
+        Some("/src/cpp/graph/util.hpp".to_string())
+    );
+    assert_eq!(
+        normalize_path("/src/./cpp//a.hpp"),
+        Some("/src/cpp/a.hpp".to_string())
+    );
+    assert_eq!(normalize_path("/src/../../a.hpp"), None);
+}
+
+#[test]
+fn test_resolve_relative() {
+    let resolver = resolver(&["/"]);
+    let including_path = "/src/cpp/graph/tree/hld.hpp";
+    assert_eq!(
+        resolver.resolve(including_path, "../util.hpp", IncludeKind::Quote),
+        Some(ResolvedInclude {
+            path: "/src/cpp/graph/util.hpp".to_string(),
+            origin: IncludeOrigin::IncludingDirectory,
+        })
+    );
+    assert_eq!(
+        resolver.resolve(including_path, "./node.hpp", IncludeKind::Quote),
+        Some(ResolvedInclude {
+            path: "/src/cpp/graph/tree/node.hpp".to_string(),
+            origin: IncludeOrigin::IncludingDirectory,
+        })
+    );
+    assert_eq!(
+        resolver.resolve(including_path, "src/cpp/math/gcd.hpp", IncludeKind::Quote),
+        Some(ResolvedInclude {
+            path: "/src/cpp/math/gcd.hpp".to_string(),
+            origin: IncludeOrigin::Root("/".to_string()),
+        })
+    );
+    assert_eq!(
+        resolver.resolve(including_path, "missing.hpp", IncludeKind::Quote),
+        None
+    );
+    assert_eq!(
+        resolver.resolve(including_path, "../../../../../a.hpp", IncludeKind::Quote),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_roots_in_order() {
+    let resolver = resolver(&["/src/cpp/math", "/src/cpp"]);
+    assert_eq!(
+        resolver.resolve("/test/cpp/gcd.test.cpp", "gcd.hpp", IncludeKind::Quote),
+        Some(ResolvedInclude {
+            path: "/src/cpp/math/gcd.hpp".to_string(),
+            origin: IncludeOrigin::Root("/src/cpp/math".to_string()),
+        })
+    );
+    assert_eq!(
+        resolver.resolve(
+            "/test/cpp/hld.test.cpp",
+            "graph/tree/hld.hpp",
+            IncludeKind::Quote
+        ),
+        Some(ResolvedInclude {
+            path: "/src/cpp/graph/tree/hld.hpp".to_string(),
+            origin: IncludeOrigin::Root("/src/cpp".to_string()),
+        })
+    );
+}
+
+#[test]
+fn test_resolve_code_info() {
+    let mut code_info = parse_code_info_from_str(
+        "#include \"../util.hpp\"\n#include \"missing.hpp\"\n",
+        "cpp".to_string(),
+    )
+    .unwrap();
+    resolver(&["/"]).resolve_code_info("/src/cpp/graph/tree/hld.hpp", &mut code_info);
+    assert_eq!(
+        code_info.filepath_dependencies,
+        vec!["/src/cpp/graph/util.hpp".to_string()]
+    );
+    assert_eq!(code_info.includes.len(), 2);
+    assert_eq!(code_info.includes[1].include, "missing.hpp");
+    assert_eq!(code_info.includes[1].resolved, None);
+}
+
+#[test]
+fn test_resolve_angle() {
+    let resolver = resolver_with_prefix(&["/", "/src/cpp"], Some("codelib"));
+    let including_path = "/test/cpp/graph/hld.test.cpp";
+    assert_eq!(
+        resolver.resolve(including_path, "codelib/graph/util.hpp", IncludeKind::Angle),
+        Some(ResolvedInclude {
+            path: "/src/cpp/graph/util.hpp".to_string(),
+            origin: IncludeOrigin::Root("/src/cpp".to_string()),
+        })
+    );
+    // without the prefix
+    assert_eq!(
+        resolver.resolve(including_path, "math/gcd.hpp", IncludeKind::Angle),
+        Some(ResolvedInclude {
+            path: "/src/cpp/math/gcd.hpp".to_string(),
+            origin: IncludeOrigin::Root("/src/cpp".to_string()),
+        })
+    );
+    // the directory of the including file is not searched
+    assert_eq!(
+        resolver.resolve(
+            "/src/cpp/graph/tree/hld.hpp",
+            "node.hpp",
+            IncludeKind::Angle
+        ),
+        None
+    );
+    assert_eq!(
+        resolver.resolve(including_path, "codelibx/util.hpp", IncludeKind::Angle),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_code_info_system_headers() {
+    let mut code_info = parse_code_info_from_str(
+        "#include <vector>\n#include <codelib/math/gcd.hpp>\n#include <bits/stdc++.h>\n#include \"missing.hpp\"\n",
+        "cpp".to_string(),
+    )
+    .unwrap();
+    resolver_with_prefix(&["/", "/src/cpp"], Some("codelib"))
+        .resolve_code_info("/test/cpp/gcd.test.cpp", &mut code_info);
+    assert_eq!(
+        code_info.filepath_dependencies,
+        vec!["/src/cpp/math/gcd.hpp".to_string()]
+    );
+    assert_eq!(
+        code_info.system_headers,
+        vec!["vector".to_string(), "bits/stdc++.h".to_string()]
+    );
+    assert_eq!(code_info.includes.len(), 4);
+}
+
+#[test]
+fn test_resolve_code_info_conditional() {
+    let mut code_info = parse_code_info_from_str(
+        "#include \"../util.hpp\"\n#ifdef USE_NODE\n#include \"node.hpp\"\n#endif\n",
+        "cpp".to_string(),
+    )
+    .unwrap();
+    resolver(&["/"]).resolve_code_info("/src/cpp/graph/tree/hld.hpp", &mut code_info);
+    assert_eq!(
+        code_info.filepath_dependencies,
+        vec!["/src/cpp/graph/util.hpp".to_string()]
+    );
+    assert_eq!(
+        code_info.conditional_dependencies,
+        vec!["/src/cpp/graph/tree/node.hpp".to_string()]
+    );
+}
+
+#[test]
+fn test_resolve_rust() {
+    let resolver = IncludeResolver::new(
+        vec!["/".to_string(), "/src/rust".to_string()],
+        Some("codelib".to_string()),
+        [
+            "/src/rust/lib.rs",
+            "/src/rust/graph/mod.rs",
+            "/src/rust/graph/dijkstra.rs",
+            "/src/rust/math.rs",
+            "/src/rust/math/gcd.rs",
+            "/test/rust/dijkstra.rs",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect(),
+    );
+    let resolved = |including_path: &str, include: &str, kind: IncludeKind| {
+        resolver
+            .resolve(including_path, include, kind)
+            .map(|resolved| (resolved.path, resolved.origin))
+    };
+    let crate_root = IncludeOrigin::CrateRoot("/src/rust".to_string());
+
+    assert_eq!(
+        resolved("/src/rust/lib.rs", "graph", IncludeKind::RustMod),
+        Some((
+            "/src/rust/graph/mod.rs".to_string(),
+            IncludeOrigin::IncludingDirectory
+        ))
+    );
+    assert_eq!(
+        resolved("/src/rust/graph/mod.rs", "dijkstra", IncludeKind::RustMod),
+        Some((
+            "/src/rust/graph/dijkstra.rs".to_string(),
+            IncludeOrigin::IncludingDirectory
+        ))
+    );
+    // submodules of a non-mod.rs file are in the directory of its name
+    assert_eq!(
+        resolved("/src/rust/math.rs", "gcd", IncludeKind::RustMod),
+        Some((
+            "/src/rust/math/gcd.rs".to_string(),
+            IncludeOrigin::IncludingDirectory
+        ))
+    );
+    assert_eq!(
+        resolved(
+            "/src/rust/graph/dijkstra.rs",
+            "crate::math::gcd::gcd",
+            IncludeKind::RustUse
+        ),
+        Some(("/src/rust/math/gcd.rs".to_string(), crate_root.clone()))
+    );
+    assert_eq!(
+        resolved(
+            "/src/rust/math/gcd.rs",
+            "crate::graph::Graph",
+            IncludeKind::RustUse
+        ),
+        Some(("/src/rust/graph/mod.rs".to_string(), crate_root))
+    );
+    assert_eq!(
+        resolved(
+            "/test/rust/dijkstra.rs",
+            "codelib::graph::dijkstra::Dijkstra",
+            IncludeKind::RustUse
+        ),
+        Some((
+            "/src/rust/graph/dijkstra.rs".to_string(),
+            IncludeOrigin::Root("/src/rust".to_string())
+        ))
+    );
+    assert_eq!(
+        resolved(
+            "/test/rust/dijkstra.rs",
+            "../../src/rust/math.rs",
+            IncludeKind::Quote
+        ),
+        Some((
+            "/src/rust/math.rs".to_string(),
+            IncludeOrigin::IncludingDirectory
+        ))
+    );
+    assert_eq!(
+        resolved(
+            "/src/rust/lib.rs",
+            "std::collections::BTreeMap",
+            IncludeKind::RustUse
+        ),
+        None
+    );
+    // no lib.rs or main.rs above
+    assert_eq!(
+        resolved(
+            "/test/rust/dijkstra.rs",
+            "crate::graph",
+            IncludeKind::RustUse
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_python() {
+    let resolver = IncludeResolver::new(
+        vec!["/".to_string(), "/src/python".to_string()],
+        Some("codelib".to_string()),
+        [
+            "/src/python/graph/__init__.py",
+            "/src/python/graph/dijkstra.py",
+            "/src/python/graph/tree/__init__.py",
+            "/src/python/graph/tree/hld.py",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect(),
+    );
+    let resolved = |including_path: &str, include: &str| {
+        resolver
+            .resolve(including_path, include, IncludeKind::PythonImport)
+            .map(|resolved| (resolved.path, resolved.origin))
+    };
+    let root = IncludeOrigin::Root("/src/python".to_string());
+    let hld = "/src/python/graph/tree/hld.py";
+
+    assert_eq!(
+        resolved(hld, "..dijkstra"),
+        Some((
+            "/src/python/graph/dijkstra.py".to_string(),
+            IncludeOrigin::IncludingDirectory
+        ))
+    );
+    assert_eq!(
+        resolved(hld, ".."),
+        Some((
+            "/src/python/graph/__init__.py".to_string(),
+            IncludeOrigin::IncludingDirectory
+        ))
+    );
+    assert_eq!(
+        resolved(hld, "."),
+        Some((
+            "/src/python/graph/tree/__init__.py".to_string(),
+            IncludeOrigin::IncludingDirectory
+        ))
+    );
+    assert_eq!(
+        resolved("/test/python/hld.py", "codelib.graph.tree"),
+        Some((
+            "/src/python/graph/tree/__init__.py".to_string(),
+            root.clone()
+        ))
+    );
+    assert_eq!(
+        resolved("/test/python/hld.py", "graph.tree.hld"),
+        Some(("/src/python/graph/tree/hld.py".to_string(), root))
+    );
+    assert_eq!(resolved("/test/python/hld.py", "os"), None);
+    assert_eq!(resolved(hld, "..dijkstra.Dijkstra"), None);
+}
+
+#[test]
+fn test_resolve_ruby() {
+    let mut code_info = parse_code_info_from_str(
+        "require 'set'\nrequire 'graph/util'\nrequire_relative '../math/gcd'\n",
+        "ruby".to_string(),
+    )
+    .unwrap();
+    let resolver = IncludeResolver::new(
+        vec!["/".to_string(), "/src/ruby".to_string()],
+        None,
+        ["/src/ruby/graph/util.rb", "/src/ruby/math/gcd.rb"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    );
+    resolver.resolve_code_info("/src/ruby/graph/dijkstra.rb", &mut code_info);
+    assert_eq!(
+        code_info.filepath_dependencies,
+        vec![
+            "/src/ruby/graph/util.rb".to_string(),
+            "/src/ruby/math/gcd.rb".to_string()
+        ]
+    );
+    assert_eq!(code_info.system_headers, vec!["set.rb".to_string()]);
+}
@@ -1,21 +1,72 @@
 mod codelib;
+mod example_exporter;
+mod include_resolver;
 mod parser;
+mod problem_index;
 mod relation_solver;
 mod repo_collector;
 
-use std::fs::File;
+#[cfg(test)]
+mod test_util;
+#[cfg(test)]
+mod unittest;
 
-use clap::Error;
 // TODO: remove several pub
-pub use codelib::{Article, CodeInfo, CodeInfoSets, Collection, Commit, SourceSets};
+pub use codelib::{
+    Article, ArticleStatus, CodeBlock, CodeInfo, CodeInfoSets, Collection, CollectionOptions,
+    Commit, ComplexityEntry, IncludeEntry, IncludeKind, IncludeOrigin, Judge, LineRange,
+    ProblemIndexEntry, ResolvedInclude, SourceSets, UsageEntry, VerifiedEntry,
+};
 
-use parser::parse_code_info_from_file;
-use parser::parse_document_from_file;
+pub use example_exporter::write_examples;
+use include_resolver::IncludeResolver;
+pub use parser::{
+    decode_source, diff_lines, encode_source, format_document_from_str, parse_verified_url,
+    ParseError, ParseErrorKind, SourceLocation,
+};
+pub use parser::{
+    parse_code_info_from_file, parse_code_info_from_reader, parse_code_info_from_str,
+};
+pub use parser::{
+    parse_document_from_file, parse_document_from_reader, parse_document_from_str,
+    parse_document_from_str_with_locale, DEFAULT_LOCALE,
+};
+use problem_index::build_problem_index;
 use relation_solver::solve_relation;
 pub use repo_collector::gather_collection;
-// use repo_collector::gather_commit_info;
+use repo_collector::gather_commit_info;
+use std::collections::BTreeSet;
 
-fn collect_code_infos(collection: &Collection) -> Vec<CodeInfoSets> {
+fn read_source_file(collection: &Collection, path: &str) -> Result<String, ParseError> {
+    let total_path = collection.complete_path_str(path);
+    std::fs::read(total_path)
+        .map_err(|err| ParseError::new(ParseErrorKind::Io(err.to_string()), None))
+        .and_then(|bytes| decode_source(&bytes, collection.fallback_encoding.as_deref()))
+        .map_err(|err| err.with_path(path))
+}
+
+fn collect_code_info(
+    collection: &Collection,
+    include_resolver: &IncludeResolver,
+    path: &str,
+    lang: &str,
+) -> CodeInfo {
+    match read_source_file(collection, path)
+        .and_then(|text| parse_code_info_from_str(&text, lang.to_string()))
+    {
+        Ok(mut code_info) => {
+            include_resolver.resolve_code_info(path, &mut code_info);
+            code_info
+        }
+        Err(err) => {
+            eprintln!("Failed to parse code info: {}\n{}", path, err);
+            CodeInfo::default()
+        }
+    }
+}
+
+pub fn collect_code_infos(collection: &Collection) -> Vec<CodeInfoSets> {
+    let include_resolver = IncludeResolver::from_collection(collection);
     collection
         .source_sets
         .iter()
@@ -24,11 +75,7 @@ fn collect_code_infos(collection: &Collection) -> Vec<CodeInfoSets> {
                 .src_paths
                 .iter()
                 .map(|src_path| {
-                    let total_path = collection.complete_path_str(src_path);
-                    let file = File::open(&total_path).expect("Failed to open file");
-                    let code_info = parse_code_info_from_file(file, source_sets.lang.clone())
-                        .expect("Failed to parse code info");
-                    code_info
+                    collect_code_info(collection, &include_resolver, src_path, &source_sets.lang)
                 })
                 .collect();
 
@@ -36,11 +83,7 @@ fn collect_code_infos(collection: &Collection) -> Vec<CodeInfoSets> {
                 .test_paths
                 .iter()
                 .map(|test_path| {
-                    let total_path = collection.complete_path_str(test_path);
-                    let file = File::open(&total_path).expect("Failed to open file");
-                    let code_info = parse_code_info_from_file(file, source_sets.lang.clone())
-                        .expect("Failed to parse code info");
-                    code_info
+                    collect_code_info(collection, &include_resolver, test_path, &source_sets.lang)
                 })
                 .collect();
 
@@ -53,12 +96,27 @@ fn collect_code_infos(collection: &Collection) -> Vec<CodeInfoSets> {
         .collect()
 }
 
-pub fn complete_articles(collection : &Collection) -> Result<Vec<Article>, String> {
-    let code_info_sets_vec = collect_code_infos(&collection);
-    let relations = match solve_relation(&collection, &code_info_sets_vec) {
-        Ok(relations) => relations,
-        Err(err) => return Err(err),
-    };
+// Adds verification URLs to the article unless already listed.
+fn merge_verified(article: &mut Article, urls: &[String]) {
+    for url in urls {
+        if !article.verified.contains(url) {
+            article.verified.push(url.clone());
+            article.verified_entries.push(parse_verified_url(url));
+        }
+    }
+}
+
+pub fn complete_articles(collection: &Collection) -> Result<Vec<Article>, String> {
+    complete_articles_with_code_infos(collection, &collect_code_infos(collection))
+}
+
+// complete_articles with `code_info_sets_vec` given by collect_code_infos,
+// which can be shared with complete_problem_index.
+pub fn complete_articles_with_code_infos(
+    collection: &Collection,
+    code_info_sets_vec: &[CodeInfoSets],
+) -> Result<Vec<Article>, String> {
+    let relations = solve_relation(collection, code_info_sets_vec)?;
 
     let mut articles = Vec::new();
     for (i, source_sets) in collection.source_sets.iter().enumerate() {
@@ -66,27 +124,127 @@ pub fn complete_articles(collection : &Collection) -> Result<Vec<Article>, Strin
         let rels = &relations.source_relations[i];
         let src_paths = &source_sets.src_paths;
         let test_paths = &source_sets.test_paths;
+        let test_code_infos = &code_info_sets_vec[i].test_code_infos;
+        let default_locale = collection
+            .default_locale
+            .as_deref()
+            .unwrap_or(DEFAULT_LOCALE);
 
         for (src_path, source_relation) in src_paths.iter().zip(rels.iter()) {
-            let total_path = collection.complete_path_str(src_path);
-            let file = File::open(&total_path).expect("Failed to open file");
-            let commits = vec![]; // TODO:
+            let commits = match gather_commit_info(&collection.base_path, src_path) {
+                Ok(commits) => commits,
+                Err(err) => {
+                    eprintln!("Failed to gather commits: {}: {}", src_path, err);
+                    Vec::new()
+                }
+            };
             let tested_by = source_relation
                 .tested_by
                 .iter()
                 .map(|i| test_paths[*i].clone())
                 .collect();
-            let article =
-                parse_document_from_file(file, src_path.clone(), lang.clone(), commits, tested_by);
+            let conditionally_tested_by: Vec<String> = source_relation
+                .conditionally_tested_by
+                .iter()
+                .map(|i| test_paths[*i].clone())
+                .collect();
+            // PROBLEM of tests which reach this source
+            let test_problems: Vec<String> = source_relation
+                .tested_by
+                .iter()
+                .filter(|i| !test_code_infos[**i].ignore)
+                .filter_map(|i| test_code_infos[*i].problem.clone())
+                .collect();
+            let file_articles = read_source_file(collection, src_path).and_then(|text| {
+                parse_document_from_str_with_locale(
+                    &text,
+                    src_path.clone(),
+                    lang.clone(),
+                    commits,
+                    tested_by,
+                    default_locale,
+                )
+            });
 
-            articles.push(match article {
-                Ok(article) => article,
+            let mut file_articles = match file_articles {
+                Ok(file_articles) => file_articles,
                 Err(err) => {
-                    eprintln!("Failed to parse article: {}: {}", src_path, err);
+                    eprintln!("Failed to parse article: {}\n{}", src_path, err);
                     continue;
                 }
-            });
+            };
+            for article in &mut file_articles {
+                merge_verified(article, &test_problems);
+                article
+                    .conditionally_tested_by
+                    .clone_from(&conditionally_tested_by);
+            }
+            articles.extend(file_articles);
         }
     }
+
+    let missing_replacements = find_missing_replacements(collection, &articles);
+    if !missing_replacements.is_empty() {
+        return Err(missing_replacements
+            .iter()
+            .map(|(id, replacement)| {
+                format!(
+                    "Replacement of deprecated article is not found: {}\n{}",
+                    id, replacement
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"));
+    }
     Ok(articles)
 }
+
+// %deprecated must refer to a source path in the collection or an article id.
+// Returns (article id, replacement) which refer to nothing.
+pub fn find_missing_replacements(
+    collection: &Collection,
+    articles: &[Article],
+) -> Vec<(String, String)> {
+    let known: BTreeSet<&str> = collection
+        .source_sets
+        .iter()
+        .flat_map(|source_sets| source_sets.src_paths.iter().map(|path| path.as_str()))
+        .chain(articles.iter().map(|article| article.id.as_str()))
+        .collect();
+    articles
+        .iter()
+        .filter_map(|article| {
+            let replacement = article.replacement.as_ref()?;
+            if known.contains(replacement.as_str()) {
+                None
+            } else {
+                Some((article.id.clone(), replacement.clone()))
+            }
+        })
+        .collect()
+}
+
+// PROBLEM of test files, which are already collected by collect_code_infos.
+// Tests with IGNORE are skipped as well as in complete_articles.
+pub fn complete_problem_index(
+    collection: &Collection,
+    code_info_sets_vec: &[CodeInfoSets],
+    articles: &[Article],
+) -> Vec<ProblemIndexEntry> {
+    let mut test_references = Vec::new();
+    for (source_sets, code_info_sets) in collection.source_sets.iter().zip(code_info_sets_vec) {
+        for (test_path, code_info) in source_sets
+            .test_paths
+            .iter()
+            .zip(&code_info_sets.test_code_infos)
+        {
+            if code_info.ignore {
+                continue;
+            }
+            if let Some(problem) = &code_info.problem {
+                test_references.push((test_path.clone(), vec![parse_verified_url(problem)]));
+            }
+        }
+    }
+    build_problem_index(articles, &test_references)
+}
@@ -0,0 +1,123 @@
+// CodeInfo of python sources. Importing `a.b.c` runs `a/__init__.py` and
+// `a/b/__init__.py` too, so every parent package is listed.
+//
+// import a.b as c        -> `a`, `a.b`
+// from .x import y, z    -> `.x`, `.x.y`, `.x.z` (y may be a submodule)
+// from . import (y,
+//                z)      -> `.`, `.y`, `.z`
+
+use super::comment::CommentMatcher;
+use super::ParseError;
+use crate::codelib::{IncludeEntry, IncludeKind};
+use crate::CodeInfo;
+
+// `..a.b` -> [`..a`, `..a.b`]; `.` -> [`.`]
+fn with_parent_packages(module: &str) -> Vec<String> {
+    let name = module.trim_start_matches('.');
+    let dots = &module[..module.len() - name.len()];
+    if name.is_empty() {
+        return vec![dots.to_string()];
+    }
+    let segments: Vec<&str> = name.split('.').collect();
+    (1..=segments.len())
+        .map(|len| format!("{}{}", dots, segments[..len].join(".")))
+        .collect()
+}
+
+// `a as b` -> `a`
+fn strip_alias(name: &str) -> &str {
+    name.split_whitespace().next().unwrap_or_default()
+}
+
+// Imported modules of a logical line.
+fn imported_modules(statement: &str) -> Vec<String> {
+    let statement = statement.trim();
+    if let Some(names) = statement.strip_prefix("import ") {
+        return names
+            .split(',')
+            .map(strip_alias)
+            .filter(|name| !name.is_empty())
+            .flat_map(with_parent_packages)
+            .collect();
+    }
+    let Some(rest) = statement.strip_prefix("from ") else {
+        return Vec::new();
+    };
+    let Some((module, names)) = rest.split_once(" import ") else {
+        return Vec::new();
+    };
+    let module = module.trim();
+    let mut modules = with_parent_packages(module);
+    let names = names.trim().trim_start_matches('(').trim_end_matches(')');
+    for name in names.split(',').map(strip_alias) {
+        if name.is_empty() || name == "*" {
+            continue;
+        }
+        if module.ends_with('.') {
+            modules.push(format!("{}{}", module, name));
+        } else {
+            modules.push(format!("{}.{}", module, name));
+        }
+    }
+    modules
+}
+
+// Code before `#` which is out of string literals.
+fn strip_line_comment(code: &str) -> &str {
+    let mut quote = None;
+    let mut chars = code.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '#') => return &code[..i],
+            _ => {}
+        }
+    }
+    code
+}
+
+pub fn parse_code_info_python(text: &str) -> Result<CodeInfo, ParseError> {
+    let mut matcher = CommentMatcher::new("python");
+    let mut includes: Vec<IncludeEntry> = Vec::new();
+    // an import statement continued by `(` or `\`
+    let mut statement = String::new();
+    for line in text.lines() {
+        // lines in strings such as indented docstrings are not statements
+        let in_string = matcher.in_string();
+        if matcher.match_line(line).is_some() || in_string {
+            continue;
+        }
+        let code = strip_line_comment(line);
+        let trimmed = code.trim_start();
+        if statement.is_empty() && !(trimmed.starts_with("import ") || trimmed.starts_with("from "))
+        {
+            continue;
+        }
+        statement.push(' ');
+        statement.push_str(code.trim_end().trim_end_matches('\\'));
+        let continued = code.trim_end().ends_with('\\')
+            || statement.matches('(').count() > statement.matches(')').count();
+        if continued {
+            continue;
+        }
+        for module in imported_modules(&statement) {
+            if includes.iter().all(|include| include.include != module) {
+                includes.push(IncludeEntry {
+                    include: module,
+                    kind: IncludeKind::PythonImport,
+                    conditional: false,
+                    resolved: None,
+                });
+            }
+        }
+        statement.clear();
+    }
+    Ok(CodeInfo {
+        includes,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,45 @@
+// CodeInfo of ruby sources.
+//
+// require_relative 'x/y'  -> Quote `x/y.rb`
+// require 'codelib/x'     -> Angle `codelib/x.rb`
+
+use super::comment::CommentMatcher;
+use super::ParseError;
+use crate::codelib::{IncludeEntry, IncludeKind};
+use crate::CodeInfo;
+
+pub fn parse_code_info_ruby(text: &str) -> Result<CodeInfo, ParseError> {
+    // unwrap is ok because the pattern is always valid
+    let re = regex::Regex::new(r#"^\s*(require_relative|require)\s*\(?\s*(?:'([^']*)'|"([^"]*)")"#)
+        .unwrap();
+    let mut matcher = CommentMatcher::new("ruby");
+    let mut includes = Vec::new();
+    for line in text.lines() {
+        if matcher.match_line(line).is_some() {
+            continue;
+        }
+        let Some(captures) = re.captures(line) else {
+            continue;
+        };
+        let path = captures.get(2).or(captures.get(3)).unwrap().as_str();
+        let kind = match &captures[1] {
+            "require_relative" => IncludeKind::Quote,
+            _ => IncludeKind::Angle,
+        };
+        let include = if path.ends_with(".rb") {
+            path.to_string()
+        } else {
+            format!("{}.rb", path)
+        };
+        includes.push(IncludeEntry {
+            include,
+            kind,
+            conditional: false,
+            resolved: None,
+        });
+    }
+    Ok(CodeInfo {
+        includes,
+        ..Default::default()
+    })
+}
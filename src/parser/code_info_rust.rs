@@ -0,0 +1,143 @@
+// CodeInfo of rust sources. Modules are found by regexps on lines out of
+// comments; `mod foo { ... }` and macros generating modules are not followed.
+//
+// mod foo;                  -> RustMod `foo`
+// #[path = "x/foo.rs"]
+// mod foo;                  -> Quote `x/foo.rs`
+// use crate::a::{b, c::D};  -> RustUse `crate::a::b`, `crate::a::c::D`
+// include!("x.rs");         -> Quote `x.rs`
+
+use super::comment::CommentMatcher;
+use super::ParseError;
+use crate::codelib::{IncludeEntry, IncludeKind};
+use crate::CodeInfo;
+
+// Cuts `// ...` out of a line. `//` in string literals is kept.
+fn strip_line_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '/' if !in_string && line[i..].starts_with("//") => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+// Splits by commas out of braces.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut begin = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&text[begin..i]);
+                begin = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&text[begin..]);
+    items
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (_, true) => prefix.to_string(),
+        _ => format!("{}::{}", prefix, path),
+    }
+}
+
+// `a::{b, c::{self, D}}` -> [`a::b`, `a::c`, `a::c::D`]
+fn expand_use_tree(prefix: &str, tree: &str, paths: &mut Vec<String>) {
+    let tree: String = tree.split_whitespace().collect::<Vec<_>>().join(" ");
+    let tree = tree.trim_start_matches("::");
+    if let (Some(open), Some(close)) = (tree.find('{'), tree.rfind('}')) {
+        let prefix = join_path(prefix, tree[..open].trim_end_matches("::").trim());
+        for item in split_top_level(&tree[open + 1..close]) {
+            if !item.trim().is_empty() {
+                expand_use_tree(&prefix, item, paths);
+            }
+        }
+        return;
+    }
+    // `x as y`
+    let path = tree.split(" as ").next().unwrap_or_default().trim();
+    let path = path.trim_end_matches("::*");
+    match path {
+        "self" | "*" => paths.push(prefix.to_string()),
+        _ => paths.push(join_path(prefix, path)),
+    }
+}
+
+pub fn parse_code_info_rust(text: &str) -> Result<CodeInfo, ParseError> {
+    let mut matcher = CommentMatcher::new("rust");
+    let code: Vec<&str> = text
+        .lines()
+        .map(|line| match matcher.match_line(line) {
+            Some(_) => "",
+            None => strip_line_comment(line),
+        })
+        .collect();
+    let code = code.join("\n");
+
+    // unwrap is ok because the patterns are always valid
+    let visibility = r"(?:pub(?:\s*\([^)]*\))?\s+)?";
+    let re_mod = regex::Regex::new(&format!(
+        r"(?m)^\s*((?:#\[[^\]]*\]\s*)*){}mod\s+(?:r#)?([A-Za-z_][A-Za-z0-9_]*)\s*;",
+        visibility
+    ))
+    .unwrap();
+    let re_path = regex::Regex::new(r#"#\[\s*path\s*=\s*"([^"]*)"\s*\]"#).unwrap();
+    let re_use = regex::Regex::new(&format!(r"(?m)^\s*{}use\s+([^;]*);", visibility)).unwrap();
+    let re_include = regex::Regex::new(r#"\binclude!\s*\(\s*"([^"]*)"\s*\)"#).unwrap();
+
+    // (position, entry) to keep the order in the file
+    let mut includes: Vec<(usize, IncludeEntry)> = Vec::new();
+    let mut push = |position: usize, include: String, kind: IncludeKind| {
+        includes.push((
+            position,
+            IncludeEntry {
+                include,
+                kind,
+                conditional: false,
+                resolved: None,
+            },
+        ))
+    };
+    for captures in re_mod.captures_iter(&code) {
+        let position = captures.get(0).unwrap().start();
+        let attributes = captures.get(1).unwrap().as_str();
+        match re_path.captures(attributes) {
+            Some(path) => push(position, path[1].to_string(), IncludeKind::Quote),
+            None => push(position, captures[2].to_string(), IncludeKind::RustMod),
+        }
+    }
+    for captures in re_use.captures_iter(&code) {
+        let position = captures.get(0).unwrap().start();
+        let mut paths = Vec::new();
+        expand_use_tree("", &captures[1], &mut paths);
+        for path in paths {
+            push(position, path, IncludeKind::RustUse);
+        }
+    }
+    for captures in re_include.captures_iter(&code) {
+        let position = captures.get(0).unwrap().start();
+        push(position, captures[1].to_string(), IncludeKind::Quote);
+    }
+    includes.sort_by_key(|(position, _)| *position);
+
+    Ok(CodeInfo {
+        includes: includes.into_iter().map(|(_, entry)| entry).collect(),
+        ..Default::default()
+    })
+}
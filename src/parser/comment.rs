@@ -0,0 +1,506 @@
+// Comment grammar of each language, and a small lexer which tells whether
+// a line is a comment.
+
+pub struct BlockCommentSyntax {
+    pub begin: &'static str,
+    pub end: &'static str,
+    // leading mark of each line in a block, e.g. ` * ` of `/* ... */`
+    pub continuation: Option<&'static str>,
+    // e.g. `=begin` of ruby, docstrings of python
+    pub line_start_only: bool,
+}
+
+pub struct StringSyntax {
+    pub begin: &'static str,
+    pub end: &'static str,
+    pub escape: bool, // backslash escapes
+    pub multiline: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RawStringSyntax {
+    Cpp,  // R"delim( ... )delim"
+    Rust, // r#" ... "#
+}
+
+pub struct CommentSyntax {
+    // checked in order; put longer prefixes first
+    pub line: &'static [&'static str],
+    pub block: &'static [BlockCommentSyntax],
+    // checked in order; put longer delimiters first
+    pub strings: &'static [StringSyntax],
+    pub raw_string: Option<RawStringSyntax>,
+    // 'a', also tolerates lifetimes and primes such as 'a or x'
+    pub char_literal: bool,
+    // lines starting with them are not comments even if they look like it
+    pub directives: &'static [&'static str],
+}
+
+const C_BLOCK: BlockCommentSyntax = BlockCommentSyntax {
+    begin: "/*",
+    end: "*/",
+    continuation: Some("*"),
+    line_start_only: false,
+};
+
+const fn string(begin: &'static str, escape: bool, multiline: bool) -> StringSyntax {
+    StringSyntax {
+        begin,
+        end: begin,
+        escape,
+        multiline,
+    }
+}
+
+const C_PREPROCESSOR_DIRECTIVES: &[&str] = &[
+    "include", "define", "undef", "pragma", "if", "ifdef", "ifndef", "elif", "else", "endif",
+    "error", "warning", "line",
+];
+
+const CPP: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[C_BLOCK],
+    strings: &[string("\"", true, false)],
+    raw_string: Some(RawStringSyntax::Cpp),
+    char_literal: true,
+    directives: &[],
+};
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[C_BLOCK],
+    strings: &[string("\"\"\"", true, true), string("\"", true, false)],
+    raw_string: None,
+    char_literal: true,
+    directives: &[],
+};
+
+const GO: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[C_BLOCK],
+    strings: &[string("\"", true, false), string("`", false, true)],
+    raw_string: None,
+    char_literal: true,
+    directives: &[],
+};
+
+const JAVASCRIPT: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[C_BLOCK],
+    strings: &[
+        string("\"", true, false),
+        string("'", true, false),
+        string("`", true, true),
+    ],
+    raw_string: None,
+    char_literal: false,
+    directives: &[],
+};
+
+const RUST: CommentSyntax = CommentSyntax {
+    line: &["///", "//!", "//"],
+    block: &[C_BLOCK],
+    strings: &[string("\"", true, true)],
+    raw_string: Some(RawStringSyntax::Rust),
+    char_literal: true,
+    directives: &[],
+};
+
+const RUBY: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[BlockCommentSyntax {
+        begin: "=begin",
+        end: "=end",
+        continuation: None,
+        line_start_only: true,
+    }],
+    strings: &[string("\"", true, true), string("'", true, true)],
+    raw_string: None,
+    char_literal: false,
+    directives: &[],
+};
+
+const PYTHON: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[
+        BlockCommentSyntax {
+            begin: "\"\"\"",
+            end: "\"\"\"",
+            continuation: None,
+            line_start_only: true,
+        },
+        BlockCommentSyntax {
+            begin: "'''",
+            end: "'''",
+            continuation: None,
+            line_start_only: true,
+        },
+    ],
+    strings: &[
+        string("\"\"\"", true, true),
+        string("'''", true, true),
+        string("\"", true, false),
+        string("'", true, false),
+    ],
+    raw_string: None,
+    char_literal: false,
+    directives: &[],
+};
+
+const SHELL: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    strings: &[string("\"", true, true), string("'", false, true)],
+    raw_string: None,
+    char_literal: false,
+    directives: &[],
+};
+
+const HASKELL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[BlockCommentSyntax {
+        begin: "{-",
+        end: "-}",
+        continuation: None,
+        line_start_only: false,
+    }],
+    strings: &[string("\"", true, false)],
+    raw_string: None,
+    char_literal: true,
+    directives: &[],
+};
+
+const LUA: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[BlockCommentSyntax {
+        begin: "--[[",
+        end: "]]",
+        continuation: None,
+        line_start_only: false,
+    }],
+    strings: &[
+        StringSyntax {
+            begin: "[[",
+            end: "]]",
+            escape: false,
+            multiline: true,
+        },
+        string("\"", true, false),
+        string("'", true, false),
+    ],
+    raw_string: None,
+    char_literal: false,
+    directives: &[],
+};
+
+const LISP: CommentSyntax = CommentSyntax {
+    line: &[";"],
+    block: &[BlockCommentSyntax {
+        begin: "#|",
+        end: "|#",
+        continuation: None,
+        line_start_only: false,
+    }],
+    strings: &[string("\"", true, true)],
+    raw_string: None,
+    char_literal: false,
+    directives: &[],
+};
+
+const SQL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[C_BLOCK],
+    strings: &[string("'", false, true)],
+    raw_string: None,
+    char_literal: false,
+    directives: &[],
+};
+
+// for unknown langs
+const FALLBACK: CommentSyntax = CommentSyntax {
+    line: &["#", "//"],
+    block: &[],
+    strings: &[string("\"", true, false)],
+    raw_string: None,
+    char_literal: false,
+    directives: C_PREPROCESSOR_DIRECTIVES,
+};
+
+impl CommentSyntax {
+    pub fn from_lang(lang: &str) -> &'static CommentSyntax {
+        match lang {
+            "cpp" | "c" => &CPP,
+            "java" | "csharp" | "kotlin" | "swift" | "scala" | "dart" => &C_LIKE,
+            "go" => &GO,
+            "javascript" | "typescript" => &JAVASCRIPT,
+            "rust" => &RUST,
+            "ruby" => &RUBY,
+            "python" => &PYTHON,
+            "sh" | "bash" | "shell" | "perl" | "nim" | "crystal" | "julia" => &SHELL,
+            "haskell" => &HASKELL,
+            "lua" => &LUA,
+            "lisp" | "scheme" | "racket" | "clojure" => &LISP,
+            "sql" => &SQL,
+            _ => &FALLBACK,
+        }
+    }
+
+    // `#include`, `#define` and so on.
+    // `# include` is regarded as a comment because it may be a sentence.
+    fn is_directive(&self, text: &str) -> bool {
+        let Some(rest) = text.strip_prefix('#') else {
+            return false;
+        };
+        self.directives.iter().any(|directive| {
+            rest.strip_prefix(directive)
+                .is_some_and(|s| !s.starts_with(is_identifier_char))
+        })
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn strip_continuation<'a>(block: &BlockCommentSyntax, text: &'a str) -> &'a str {
+    match block.continuation {
+        Some(continuation) => text.strip_prefix(continuation).unwrap_or(text),
+        None => text,
+    }
+}
+
+// Returns the position just after `end`, skipping escaped chars.
+fn find_string_end(text: &str, end: &str, escape: bool) -> Option<usize> {
+    let mut i = 0;
+    while i < text.len() {
+        if text[i..].starts_with(end) {
+            return Some(i + end.len());
+        }
+        let c = text[i..].chars().next().unwrap();
+        i += c.len_utf8();
+        if escape && c == '\\' {
+            if let Some(escaped) = text[i..].chars().next() {
+                i += escaped.len_utf8();
+            }
+        }
+    }
+    None
+}
+
+// Returns the closing delimiter and the length of the opening one.
+fn match_raw_string_begin(kind: RawStringSyntax, text: &str) -> Option<(String, usize)> {
+    match kind {
+        RawStringSyntax::Cpp => {
+            let rest = text.strip_prefix("R\"")?;
+            let paren = rest.find('(')?;
+            let delimiter = &rest[..paren];
+            if delimiter.len() > 16 || delimiter.contains([' ', ')', '\\', '"']) {
+                return None;
+            }
+            Some((format!("){}\"", delimiter), 2 + paren + 1))
+        }
+        RawStringSyntax::Rust => {
+            let rest = text.strip_prefix('r')?;
+            let hashes = rest.len() - rest.trim_start_matches('#').len();
+            if !rest[hashes..].starts_with('"') {
+                return None;
+            }
+            Some((format!("\"{}", "#".repeat(hashes)), 1 + hashes + 1))
+        }
+    }
+}
+
+enum LexState {
+    Code,
+    BlockComment(&'static BlockCommentSyntax),
+    String { end: String, escape: bool },
+}
+
+// Tracks block comments and multi-line string literals across lines.
+pub struct CommentMatcher {
+    syntax: &'static CommentSyntax,
+    state: LexState,
+}
+
+impl CommentMatcher {
+    pub fn new(lang: &str) -> Self {
+        Self {
+            syntax: CommentSyntax::from_lang(lang),
+            state: LexState::Code,
+        }
+    }
+
+    pub fn is_line_comment_marker(&self, marker: &str) -> bool {
+        self.syntax.line.contains(&marker)
+    }
+
+    // Whether the next line starts in a multi-line string literal.
+    pub fn in_string(&self) -> bool {
+        matches!(self.state, LexState::String { .. })
+    }
+
+    // If the whole line is a comment, returns the comment body without
+    // comment marks. The returned str is always a slice of `line`.
+    pub fn match_line<'a>(&mut self, line: &'a str) -> Option<&'a str> {
+        match std::mem::replace(&mut self.state, LexState::Code) {
+            LexState::BlockComment(block) => {
+                let body = line.trim_start();
+                let body = match body.find(block.end) {
+                    Some(pos) => {
+                        self.scan_code(&body[pos + block.end.len()..]);
+                        body[..pos].trim_end()
+                    }
+                    None => {
+                        self.state = LexState::BlockComment(block);
+                        body
+                    }
+                };
+                Some(strip_continuation(block, body).trim_start())
+            }
+            LexState::String { end, escape } => {
+                // a line in a string literal is never a comment
+                match find_string_end(line, &end, escape) {
+                    Some(pos) => self.scan_code(&line[pos..]),
+                    None => self.state = LexState::String { end, escape },
+                }
+                None
+            }
+            LexState::Code => self.match_code_line(line),
+        }
+    }
+
+    fn match_code_line<'a>(&mut self, line: &'a str) -> Option<&'a str> {
+        let body = line.trim_start();
+        if self.syntax.is_directive(body) {
+            return None;
+        }
+
+        for block in self.syntax.block {
+            if block.line_start_only && body.len() != line.len() {
+                continue;
+            }
+            if let Some(rest) = body.strip_prefix(block.begin) {
+                let rest = match rest.find(block.end) {
+                    Some(pos) => {
+                        self.scan_code(&rest[pos + block.end.len()..]);
+                        rest[..pos].trim_end()
+                    }
+                    None => {
+                        self.state = LexState::BlockComment(block);
+                        rest
+                    }
+                };
+                return Some(strip_continuation(block, rest).trim_start());
+            }
+        }
+
+        for prefix in self.syntax.line {
+            if body.starts_with(prefix) {
+                return Some(body.trim_start_matches(prefix).trim_start());
+            }
+        }
+
+        self.scan_code(line);
+        None
+    }
+
+    // Walks a code fragment to find where string literals and block comments
+    // begin and whether they continue to the next line.
+    fn scan_code(&mut self, text: &str) {
+        let mut i = 0;
+        'scan: while i < text.len() {
+            let rest = &text[i..];
+
+            if self
+                .syntax
+                .line
+                .iter()
+                .any(|prefix| rest.starts_with(prefix))
+            {
+                return;
+            }
+
+            for block in self.syntax.block {
+                if block.line_start_only {
+                    continue;
+                }
+                if let Some(after) = rest.strip_prefix(block.begin) {
+                    match after.find(block.end) {
+                        Some(pos) => {
+                            i += block.begin.len() + pos + block.end.len();
+                            continue 'scan;
+                        }
+                        None => {
+                            self.state = LexState::BlockComment(block);
+                            return;
+                        }
+                    }
+                }
+            }
+
+            let prev_is_identifier = text[..i]
+                .chars()
+                .next_back()
+                .is_some_and(is_identifier_char);
+
+            if let Some(kind) = self.syntax.raw_string {
+                // allow encoding prefixes such as u8R"(...)" and br"..."
+                let prefix_ok = !prev_is_identifier
+                    || matches!(
+                        text[..i].chars().next_back(),
+                        Some('u' | 'U' | 'L' | '8' | 'b')
+                    );
+                if prefix_ok {
+                    if let Some((end, begin_len)) = match_raw_string_begin(kind, rest) {
+                        match find_string_end(&rest[begin_len..], &end, false) {
+                            Some(pos) => {
+                                i += begin_len + pos;
+                                continue 'scan;
+                            }
+                            None => {
+                                self.state = LexState::String { end, escape: false };
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+
+            for string in self.syntax.strings {
+                if let Some(after) = rest.strip_prefix(string.begin) {
+                    match find_string_end(after, string.end, string.escape) {
+                        Some(pos) => {
+                            i += string.begin.len() + pos;
+                            continue 'scan;
+                        }
+                        None => {
+                            if string.multiline {
+                                self.state = LexState::String {
+                                    end: string.end.to_string(),
+                                    escape: string.escape,
+                                };
+                            }
+                            return;
+                        }
+                    }
+                }
+            }
+
+            if self.syntax.char_literal {
+                if let Some(after) = rest.strip_prefix('\'') {
+                    // 'a' or '\n'; otherwise a lifetime or a prime
+                    let mut chars = after.chars();
+                    let len = match chars.next() {
+                        Some('\\') => find_string_end(after, "'", true).map(|pos| 1 + pos),
+                        Some(c) if chars.next() == Some('\'') => Some(1 + c.len_utf8() + 1),
+                        _ => None,
+                    };
+                    i += len.unwrap_or(1);
+                    continue 'scan;
+                }
+            }
+
+            i += rest.chars().next().unwrap().len_utf8();
+        }
+    }
+}
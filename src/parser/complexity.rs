@@ -0,0 +1,488 @@
+// Parser of %complexity lines. Each line is
+//
+// operation: O(time)
+// operation: O(time), O(space)
+//
+// and the big-O expressions are normalised, e.g. `O(NlogN)` to `O(N log N)`
+// and `O(log(N)^2)` to `O(log^2 N)`.
+
+use std::ops::Range;
+
+use crate::codelib::ComplexityEntry;
+
+// Message and byte range in the parsed text.
+pub struct ComplexityError {
+    pub message: String,
+    pub span: Range<usize>,
+}
+
+impl ComplexityError {
+    fn new(message: impl Into<String>, span: Range<usize>) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+// Words which are read as functions; other letters are variables.
+const FUNCTIONS: &[&str] = &["log", "sqrt", "min", "max", "alpha", "exp"];
+// Functions whose argument needs parentheses
+const FUNCTIONS_WITH_ARGS: &[&str] = &["min", "max"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Variable(String),
+    Function(&'static str),
+    Plus,
+    Star,
+    Slash,
+    Caret,
+    Bang,
+    Underscore,
+    Open,
+    Close,
+    Comma,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Number(s) | Token::Variable(s) => format!("`{}`", s),
+            Token::Function(name) => format!("`{}`", name),
+            Token::Plus => "`+`".to_string(),
+            Token::Star => "`*`".to_string(),
+            Token::Slash => "`/`".to_string(),
+            Token::Caret => "`^`".to_string(),
+            Token::Bang => "`!`".to_string(),
+            Token::Underscore => "`_`".to_string(),
+            Token::Open => "`(`".to_string(),
+            Token::Close => "`)`".to_string(),
+            Token::Comma => "`,`".to_string(),
+        }
+    }
+}
+
+// Lowercase words are split into known functions and single-letter
+// variables (e.g. `nlogn` is `n log n`); uppercase letters are variables.
+fn tokenize(text: &str, offset: usize) -> Result<Vec<(Token, Range<usize>)>, ComplexityError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let begin = offset + i;
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '0'..='9' => {
+                let mut end = i + c.len_utf8();
+                while let Some(&(j, d)) = chars.peek() {
+                    if !(d.is_ascii_digit() || d == '.') {
+                        break;
+                    }
+                    end = j + d.len_utf8();
+                    chars.next();
+                }
+                tokens.push((Token::Number(text[i..end].to_string()), begin..offset + end));
+                continue;
+            }
+            c if c.is_ascii_lowercase() => {
+                if let Some(name) = FUNCTIONS.iter().find(|name| text[i..].starts_with(**name)) {
+                    for _ in 1..name.len() {
+                        chars.next();
+                    }
+                    tokens.push((Token::Function(name), begin..begin + name.len()));
+                    continue;
+                }
+                Token::Variable(c.to_string())
+            }
+            'α' => Token::Function("alpha"),
+            c if c.is_alphabetic() => Token::Variable(c.to_string()),
+            '+' => Token::Plus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '^' => Token::Caret,
+            '!' => Token::Bang,
+            '_' => Token::Underscore,
+            '(' | '{' => Token::Open,
+            ')' | '}' => Token::Close,
+            ',' => Token::Comma,
+            _ => {
+                return Err(ComplexityError::new(
+                    format!("unexpected character `{}`", c),
+                    begin..begin + c.len_utf8(),
+                ))
+            }
+        };
+        tokens.push((token, begin..begin + c.len_utf8()));
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(String),
+    Variable(String),
+    Sum(Vec<Expr>),
+    Product(Vec<(bool, Expr)>), // (is divisor, factor)
+    Power(Box<Expr>, Box<Expr>),
+    Factorial(Box<Expr>),
+    Function {
+        name: &'static str,
+        power: Option<Box<Expr>>,
+        base: Option<Box<Expr>>,
+        args: Vec<Expr>,
+    },
+}
+
+impl Expr {
+    fn is_atom(&self) -> bool {
+        matches!(self, Expr::Number(_) | Expr::Variable(_))
+    }
+
+    fn render(&self) -> String {
+        match self {
+            Expr::Number(s) | Expr::Variable(s) => s.clone(),
+            Expr::Sum(terms) => terms
+                .iter()
+                .map(|term| term.render())
+                .collect::<Vec<_>>()
+                .join(" + "),
+            Expr::Product(factors) => {
+                let mut s = String::new();
+                for (i, (divisor, factor)) in factors.iter().enumerate() {
+                    if *divisor {
+                        s.push_str(" / ");
+                    } else if i > 0 {
+                        s.push(' ');
+                    }
+                    match factor {
+                        Expr::Sum(_) => s.push_str(&format!("({})", factor.render())),
+                        Expr::Product(_) if *divisor => {
+                            s.push_str(&format!("({})", factor.render()))
+                        }
+                        _ => s.push_str(&factor.render()),
+                    }
+                }
+                s
+            }
+            Expr::Power(base, exponent) => {
+                let base = match base.as_ref() {
+                    Expr::Number(_) | Expr::Variable(_) => base.render(),
+                    _ => format!("({})", base.render()),
+                };
+                format!("{}^{}", base, render_exponent(exponent))
+            }
+            Expr::Factorial(operand) => format!("{}!", render_exponent(operand)),
+            Expr::Function {
+                name,
+                power,
+                base,
+                args,
+            } => {
+                let mut s = name.to_string();
+                if let Some(power) = power {
+                    s.push('^');
+                    s.push_str(&render_exponent(power));
+                }
+                if let Some(base) = base {
+                    s.push('_');
+                    s.push_str(&render_exponent(base));
+                }
+                match args.as_slice() {
+                    // log N, log log N
+                    [arg]
+                        if *name == "log"
+                            && (arg.is_atom()
+                                || matches!(arg, Expr::Function { name: "log", .. })) =>
+                    {
+                        s.push(' ');
+                        s.push_str(&arg.render());
+                    }
+                    _ => {
+                        let args: Vec<String> = args.iter().map(|arg| arg.render()).collect();
+                        s.push_str(&format!("({})", args.join(", ")));
+                    }
+                }
+                s
+            }
+        }
+    }
+}
+
+fn render_exponent(exponent: &Expr) -> String {
+    if exponent.is_atom() {
+        exponent.render()
+    } else {
+        format!("({})", exponent.render())
+    }
+}
+
+struct ExprParser {
+    tokens: Vec<(Token, Range<usize>)>,
+    position: usize,
+    end: usize, // end of the text, for errors at the end
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn span(&self) -> Range<usize> {
+        match self.tokens.get(self.position) {
+            Some((_, span)) => span.clone(),
+            None => self.end..self.end,
+        }
+    }
+
+    fn unexpected(&self) -> ComplexityError {
+        match self.peek() {
+            Some(token) => {
+                ComplexityError::new(format!("unexpected {}", token.describe()), self.span())
+            }
+            None => ComplexityError::new("unexpected end of expression", self.span()),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ComplexityError> {
+        if self.peek() == Some(&expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, ComplexityError> {
+        let mut terms = vec![self.product()?];
+        while self.peek() == Some(&Token::Plus) {
+            self.position += 1;
+            terms.push(self.product()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.pop().unwrap()
+        } else {
+            Expr::Sum(terms)
+        })
+    }
+
+    fn product(&mut self) -> Result<Expr, ComplexityError> {
+        let mut factors = vec![(false, self.power()?)];
+        loop {
+            let divisor = match self.peek() {
+                Some(Token::Star) => false,
+                Some(Token::Slash) => true,
+                // implicit multiplication, e.g. `N log N`
+                Some(Token::Number(_) | Token::Variable(_) | Token::Function(_) | Token::Open) => {
+                    factors.push((false, self.power()?));
+                    continue;
+                }
+                _ => break,
+            };
+            self.position += 1;
+            factors.push((divisor, self.power()?));
+        }
+        Ok(if factors.len() == 1 {
+            factors.pop().unwrap().1
+        } else {
+            Expr::Product(factors)
+        })
+    }
+
+    fn power(&mut self) -> Result<Expr, ComplexityError> {
+        let base = self.factorial()?;
+        if self.peek() != Some(&Token::Caret) {
+            return Ok(base);
+        }
+        self.position += 1;
+        let exponent = self.exponent()?;
+        Ok(match base {
+            // log(N)^2 is written as log^2 N
+            Expr::Function {
+                name,
+                power: None,
+                base,
+                args,
+            } => Expr::Function {
+                name,
+                power: Some(Box::new(exponent)),
+                base,
+                args,
+            },
+            base => Expr::Power(Box::new(base), Box::new(exponent)),
+        })
+    }
+
+    // Postfix `!`, e.g. `N!` and `(N - K)!`
+    fn factorial(&mut self) -> Result<Expr, ComplexityError> {
+        let mut expr = self.atom()?;
+        while self.peek() == Some(&Token::Bang) {
+            self.position += 1;
+            expr = Expr::Factorial(Box::new(expr));
+        }
+        Ok(expr)
+    }
+
+    // After `^` or `_`: a number, a variable or a parenthesized expression
+    fn exponent(&mut self) -> Result<Expr, ComplexityError> {
+        match self.peek().cloned() {
+            Some(Token::Number(s)) => {
+                self.position += 1;
+                Ok(Expr::Number(s))
+            }
+            Some(Token::Variable(s)) => {
+                self.position += 1;
+                Ok(Expr::Variable(s))
+            }
+            Some(Token::Open) => {
+                self.position += 1;
+                let expr = self.sum()?;
+                self.expect(Token::Close)?;
+                Ok(expr)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, ComplexityError> {
+        match self.peek().cloned() {
+            Some(Token::Number(s)) => {
+                self.position += 1;
+                Ok(Expr::Number(s))
+            }
+            Some(Token::Variable(s)) => {
+                self.position += 1;
+                Ok(Expr::Variable(s))
+            }
+            Some(Token::Open) => {
+                self.position += 1;
+                let expr = self.sum()?;
+                self.expect(Token::Close)?;
+                Ok(expr)
+            }
+            Some(Token::Function(name)) => {
+                self.position += 1;
+                self.function(name)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn function(&mut self, name: &'static str) -> Result<Expr, ComplexityError> {
+        let mut power = None;
+        let mut base = None;
+        loop {
+            match self.peek() {
+                Some(Token::Caret) if power.is_none() => {
+                    self.position += 1;
+                    power = Some(Box::new(self.exponent()?));
+                }
+                Some(Token::Underscore) if base.is_none() => {
+                    self.position += 1;
+                    base = Some(Box::new(self.exponent()?));
+                }
+                _ => break,
+            }
+        }
+        let args = if self.peek() == Some(&Token::Open) {
+            self.position += 1;
+            let mut args = vec![self.sum()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+                args.push(self.sum()?);
+            }
+            self.expect(Token::Close)?;
+            args
+        } else if FUNCTIONS_WITH_ARGS.contains(&name) {
+            return Err(self.unexpected());
+        } else {
+            // log N, log log N
+            vec![self.power()?]
+        };
+        Ok(Expr::Function {
+            name,
+            power,
+            base,
+            args,
+        })
+    }
+}
+
+// Parses and normalises `O(...)` starting at `offset` of the line.
+fn parse_big_o(text: &str, offset: usize) -> Result<String, ComplexityError> {
+    let trimmed = text.trim_start();
+    let offset = offset + text.len() - trimmed.len();
+    let inner = trimmed
+        .strip_prefix('O')
+        .map(|s| s.trim_start())
+        .and_then(|s| s.strip_prefix('('))
+        .and_then(|s| s.trim_end().strip_suffix(')'));
+    let inner = match inner {
+        Some(inner) => inner,
+        None => {
+            return Err(ComplexityError::new(
+                "complexity must be written as `O(...)`",
+                offset..offset + trimmed.trim_end().len(),
+            ))
+        }
+    };
+    let inner_offset = offset + (inner.as_ptr() as usize - trimmed.as_ptr() as usize);
+    let mut parser = ExprParser {
+        tokens: tokenize(inner, inner_offset)?,
+        position: 0,
+        end: inner_offset + inner.len(),
+    };
+    let expr = parser.sum()?;
+    if parser.position < parser.tokens.len() {
+        return Err(parser.unexpected());
+    }
+    Ok(format!("O({})", expr.render()))
+}
+
+// Splits `O(a), O(b)` at the comma outside parentheses.
+fn split_top_level_comma(text: &str) -> Option<(&str, &str)> {
+    let mut depth = 0i32;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth -= 1,
+            ',' if depth == 0 => return Some((&text[..i], &text[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn parse_complexity_entry(text: &str) -> Result<ComplexityEntry, ComplexityError> {
+    // operation may contain `::`, but big-O does not contain `:`
+    let colon = match text.rfind(':') {
+        Some(colon) => colon,
+        None => {
+            return Err(ComplexityError::new(
+                "expected `operation: O(...)`",
+                0..text.len(),
+            ))
+        }
+    };
+    let operation = text[..colon].trim();
+    if operation.is_empty() {
+        return Err(ComplexityError::new("operation is empty", colon..colon + 1));
+    }
+    let value = &text[colon + 1..];
+    let value_offset = colon + 1;
+    let (time, space) = match split_top_level_comma(value) {
+        Some((time, space)) => {
+            let space_offset = value_offset + time.len() + 1;
+            (
+                parse_big_o(time, value_offset)?,
+                Some(parse_big_o(space, space_offset)?),
+            )
+        }
+        None => (parse_big_o(value, value_offset)?, None),
+    };
+    Ok(ComplexityEntry {
+        operation: operation.to_string(),
+        time,
+        space,
+    })
+}
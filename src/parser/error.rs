@@ -0,0 +1,156 @@
+use std::fmt;
+
+// Position of the offending text in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub line_number: usize, // 1-based
+    pub column: usize,      // 1-based, counted in chars
+    pub line: String,
+    pub width: usize, // chars to be underlined
+}
+
+impl SourceLocation {
+    // `part` must be a substring slice of `line`.
+    pub fn of_part(line_number: usize, line: &str, part: &str) -> Self {
+        let offset = (part.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+        let offset = offset.min(line.len());
+        Self {
+            line_number,
+            column: line[..offset].chars().count() + 1,
+            line: line.to_string(),
+            width: part.chars().count().max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnknownBlockAnchor(String),
+    NestedDocBlock,
+    NestedCodeBlock,
+    UnclosedBlock,
+    EmptyCode,
+    MissingTitle,
+    UnterminatedCodeFence,
+    DuplicateArticleName(String),
+    DuplicateCodeBlockName(String),
+    InvalidComplexity(String),
+    InvalidStatus(String),
+    ConflictingStatus,
+    Unformattable(String),
+    InvalidFrontMatter(String),
+    MixedDocSyntax,
+    InvalidEncoding(String),
+    UnknownEncoding(String),
+    Io(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnknownBlockAnchor(anchor) => {
+                write!(f, "unknown block anchor `{}`", anchor)
+            }
+            ParseErrorKind::NestedDocBlock | ParseErrorKind::NestedCodeBlock => {
+                write!(f, "nested block anchor is not allowed")
+            }
+            ParseErrorKind::UnclosedBlock => write!(f, "block anchor is not closed"),
+            ParseErrorKind::EmptyCode => write!(f, "code block is empty"),
+            ParseErrorKind::MissingTitle => write!(f, "title is required"),
+            ParseErrorKind::UnterminatedCodeFence => write!(f, "``` fence is not closed"),
+            ParseErrorKind::DuplicateArticleName(name) => {
+                write!(f, "article name `{}` is already used in this file", name)
+            }
+            ParseErrorKind::DuplicateCodeBlockName(name) => {
+                write!(
+                    f,
+                    "code block name `{}` is already used in this article",
+                    name
+                )
+            }
+            ParseErrorKind::InvalidComplexity(message) => {
+                write!(f, "invalid complexity: {}", message)
+            }
+            ParseErrorKind::InvalidStatus(status) => write!(
+                f,
+                "unknown status `{}`; expected experimental, stable or deprecated",
+                status
+            ),
+            ParseErrorKind::ConflictingStatus => {
+                write!(f, "status conflicts with %deprecated")
+            }
+            ParseErrorKind::Unformattable(reason) => {
+                write!(f, "cannot format DOC block: {}", reason)
+            }
+            ParseErrorKind::InvalidFrontMatter(message) => {
+                write!(f, "invalid front matter: {}", message)
+            }
+            ParseErrorKind::MixedDocSyntax => write!(
+                f,
+                "`%=BEGIN DOC` and `%=FRONT MATTER` are not allowed in the same file"
+            ),
+            ParseErrorKind::InvalidEncoding(encoding) => {
+                write!(f, "invalid byte sequence for {}", encoding)
+            }
+            ParseErrorKind::UnknownEncoding(label) => write!(f, "unknown encoding `{}`", label),
+            ParseErrorKind::Io(message) => write!(f, "failed to read: {}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub path: String,
+    pub kind: ParseErrorKind,
+    pub location: Option<SourceLocation>,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, location: Option<SourceLocation>) -> Self {
+        Self {
+            path: String::new(),
+            kind,
+            location,
+        }
+    }
+
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+}
+
+// Render like rustc:
+//
+// error: unknown block anchor `BEGIN FOO`
+//  --> /src/cpp/a.hpp:3:6
+//   |
+// 3 | // %=BEGIN FOO
+//   |      ^^^^^^^^^
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.kind)?;
+        match &self.location {
+            Some(location) => {
+                let gutter = " ".repeat(location.line_number.to_string().len());
+                writeln!(
+                    f,
+                    "{} --> {}:{}:{}",
+                    gutter, self.path, location.line_number, location.column
+                )?;
+                writeln!(f, "{} |", gutter)?;
+                writeln!(f, "{} | {}", location.line_number, location.line)?;
+                write!(
+                    f,
+                    "{} | {}{}",
+                    gutter,
+                    " ".repeat(location.column - 1),
+                    "^".repeat(location.width)
+                )
+            }
+            None => write!(f, " --> {}", self.path),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
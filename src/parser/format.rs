@@ -0,0 +1,217 @@
+// Formatter of DOC blocks. A DOC block is re-emitted from the parsed sections
+// in canonical order and style:
+//
+// // %=BEGIN DOC
+// // %title
+// // ...
+// //
+// // %usage
+// // ```
+// raw lines in the fence
+// // ```
+// // %=END DOC
+//
+// Lines out of DOC blocks are kept as they are. DOC blocks must be written in
+// line comments.
+
+use super::comment::CommentMatcher;
+use super::source_text::normalize_source;
+use super::{ArticleFragment, ParseError, ParseErrorKind, ParserInternalState, SectionItem};
+
+// Comment marker and indent, taken from the `%=BEGIN DOC` line.
+struct DocStyle {
+    indent: String,
+    marker: String,
+}
+
+impl DocStyle {
+    fn line(&self, text: &str) -> String {
+        if text.is_empty() {
+            format!("{}{}", self.indent, self.marker)
+        } else {
+            format!("{}{} {}", self.indent, self.marker, text)
+        }
+    }
+}
+
+fn doc_style(fragment: &ArticleFragment, lang: &str) -> Result<DocStyle, ParseError> {
+    // DOC block exists because fragments without DOC block are skipped
+    let location = fragment.doc_location.clone().unwrap();
+    let prefix = &location.line[..location.line.find("%=").unwrap_or(0)];
+    let marker = prefix.trim();
+    if !CommentMatcher::new(lang).is_line_comment_marker(marker) {
+        return Err(ParseError::new(
+            ParseErrorKind::Unformattable("DOC block must be written in line comments".to_string()),
+            Some(location),
+        ));
+    }
+    Ok(DocStyle {
+        indent: prefix[..prefix.len() - prefix.trim_start().len()].to_string(),
+        marker: marker.to_string(),
+    })
+}
+
+// Text lines without leading, trailing and repeated blank lines.
+fn push_items(output: &mut Vec<String>, style: &DocStyle, items: &[SectionItem]) {
+    let mut pending_blank = false;
+    let mut empty = true;
+    for item in items {
+        match item {
+            SectionItem::Text(text) if text.is_empty() => {
+                pending_blank = !empty;
+            }
+            SectionItem::Text(text) => {
+                if pending_blank {
+                    output.push(style.line(""));
+                }
+                output.push(style.line(text));
+                pending_blank = false;
+                empty = false;
+            }
+            SectionItem::Fence { info, lines } => {
+                if pending_blank {
+                    output.push(style.line(""));
+                }
+                output.push(style.line(&format!("```{}", info)));
+                output.extend(lines.iter().cloned());
+                output.push(style.line("```"));
+                pending_blank = false;
+                empty = false;
+            }
+        }
+    }
+}
+
+fn format_fragment(fragment: &ArticleFragment, lang: &str) -> Result<Vec<String>, ParseError> {
+    if let Some(location) = &fragment.stray_code_location {
+        return Err(ParseError::new(
+            ParseErrorKind::Unformattable(
+                "code out of ``` fences in DOC block would be lost".to_string(),
+            ),
+            Some(location.clone()),
+        ));
+    }
+    if let Some(location) = &fragment.duplicate_section_location {
+        return Err(ParseError::new(
+            ParseErrorKind::Unformattable(
+                "repeated section would lose all but the last one".to_string(),
+            ),
+            Some(location.clone()),
+        ));
+    }
+    let style = doc_style(fragment, lang)?;
+    let mut output = Vec::new();
+    output.push(style.line(&match &fragment.name {
+        Some(name) => format!("%=BEGIN DOC {}", name),
+        None => "%=BEGIN DOC".to_string(),
+    }));
+    // sections are ordered by SectionAnchor, and the text before the first
+    // section comes first
+    let mut first = true;
+    for (section, items) in &fragment.section_items {
+        if section.is_none()
+            && items
+                .iter()
+                .all(|item| *item == SectionItem::Text(String::new()))
+        {
+            continue;
+        }
+        if !first {
+            output.push(style.line(""));
+        }
+        first = false;
+        if let Some(section) = section {
+            output.push(style.line(&format!("%{}", section.name())));
+        }
+        push_items(&mut output, &style, items);
+    }
+    output.push(style.line("%=END DOC"));
+    Ok(output)
+}
+
+// Returns the formatted text. BOM, line endings and the final newline are kept.
+pub fn format_document_from_str(text: &str, lang: &str) -> Result<String, ParseError> {
+    let bom = if text.starts_with('\u{feff}') {
+        "\u{feff}"
+    } else {
+        ""
+    };
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let final_newline = text.ends_with('\n') || text.ends_with('\r');
+    let text = normalize_source(text);
+
+    let mut parser_state = ParserInternalState::new(lang);
+    for line in text.lines() {
+        parser_state.parse_line(line)?;
+    }
+    parser_state.finish()?;
+
+    let lines: Vec<&str> = text.lines().collect();
+    let mut output: Vec<String> = Vec::new();
+    let mut next_line = 0; // 0-based
+    for fragment in &parser_state.finished_fragments {
+        let doc_line_range = match fragment.doc_line_range {
+            Some(doc_line_range) => doc_line_range,
+            None => continue,
+        };
+        // front matter is kept as written
+        if fragment.front_matter {
+            continue;
+        }
+        output.extend(
+            lines[next_line..doc_line_range.begin - 1]
+                .iter()
+                .map(|line| line.to_string()),
+        );
+        output.extend(format_fragment(fragment, lang)?);
+        next_line = doc_line_range.end;
+    }
+    output.extend(lines[next_line..].iter().map(|line| line.to_string()));
+
+    let mut formatted = bom.to_string();
+    formatted.push_str(&output.join(newline));
+    if final_newline {
+        formatted.push_str(newline);
+    }
+    Ok(formatted)
+}
+
+// Unified diff with a single hunk from the first changed line to the last one.
+pub fn diff_lines(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_changed = &old_lines[prefix..old_lines.len() - suffix];
+    let new_changed = &new_lines[prefix..new_lines.len() - suffix];
+    if old_changed.is_empty() && new_changed.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", path, path);
+    // an empty range starts at the line before it
+    let start = |changed: &[&str]| prefix + usize::from(!changed.is_empty());
+    diff.push_str(&format!(
+        "@@ -{},{} +{},{} @@\n",
+        start(old_changed),
+        old_changed.len(),
+        start(new_changed),
+        new_changed.len()
+    ));
+    for line in old_changed {
+        diff.push_str(&format!("-{}\n", line));
+    }
+    for line in new_changed {
+        diff.push_str(&format!("+{}\n", line));
+    }
+    diff
+}
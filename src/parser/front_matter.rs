@@ -0,0 +1,129 @@
+// Front matter, an alternative syntax of DOC blocks. A file uses it if it
+// has `%=FRONT MATTER` before the first block; then each `---` out of blocks
+// starts a DOC block.
+//
+// // %=FRONT MATTER
+// // ---
+// // name: min              (optional, same as `%=BEGIN DOC min`)
+// // title: SparseTable
+// // title.en: Sparse table
+// // overview: |
+// //   lines indented from keys
+// // verified:
+// //   - https://judge.yosupo.jp/problem/staticrmq
+// // words: [rmq, sparse table]
+// // ---
+//
+// Keys are section names; values are the same as the text of the sections.
+
+use super::{
+    BlockAnchor, ParseError, ParseErrorKind, ParserInternalState, SectionAnchor, SectionItem,
+    SourceLocation,
+};
+
+pub fn is_front_matter_delimiter(comment: &str) -> bool {
+    comment.trim_end() == "---"
+}
+
+// `key: value` -> (key, value)
+fn split_key_value(text: &str) -> Option<(&str, &str)> {
+    let (key, rest) = text.split_once(':')?;
+    let valid_key = key
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+    if key.is_empty() || !valid_key || !(rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+        return None;
+    }
+    Some((key, rest.trim()))
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+impl ParserInternalState {
+    // Called for comments between `---` and `---`.
+    pub(super) fn parse_front_matter_line(
+        &mut self,
+        line: &str,
+        comment: &str,
+    ) -> Result<(), ParseError> {
+        debug_assert!(self.block_anchor == Some(BlockAnchor::Article));
+        let text = comment.trim_end();
+        if text.is_empty() {
+            if self.section_anchor.is_some() {
+                self.push_front_matter_value(line, text)?;
+            }
+            return Ok(());
+        }
+
+        let column = comment.as_ptr() as usize - line.as_ptr() as usize;
+        if column > self.front_matter_column {
+            // a line of the value of the last key
+            if self.section_anchor.is_none() {
+                return Err(self.front_matter_error(line, text, "value without key"));
+            }
+            let value = match text.strip_prefix('-') {
+                Some(item)
+                    if self.front_matter_list && (item.is_empty() || item.starts_with(' ')) =>
+                {
+                    unquote(item.trim())
+                }
+                _ => text,
+            };
+            return self.push_front_matter_value(line, value);
+        }
+
+        if is_front_matter_delimiter(text) {
+            return self.end_doc();
+        }
+
+        let (key, value) = match split_key_value(text) {
+            Some(key_value) => key_value,
+            None => return Err(self.front_matter_error(line, text, "expected `key: value`")),
+        };
+        if key == "name" {
+            self.finish_anchor();
+            self.fragment.name = Some(unquote(value).to_string());
+            return Ok(());
+        }
+        self.start_section(
+            SectionAnchor::from_str(key).unwrap_or_else(|| SectionAnchor::Unknown(key.to_string())),
+        );
+        self.front_matter_list = value.is_empty();
+        match value {
+            "" | "|" | ">" => {}
+            _ if value.starts_with('[') && value.ends_with(']') => {
+                let items = &value[1..value.len() - 1];
+                for item in items.split(',').map(|item| unquote(item.trim())) {
+                    if !item.is_empty() {
+                        self.push_front_matter_value(line, item)?;
+                    }
+                }
+            }
+            _ => self.push_front_matter_value(line, unquote(value))?,
+        }
+        Ok(())
+    }
+
+    // `value` must be a substring slice of `line`.
+    fn push_front_matter_value(&mut self, line: &str, value: &str) -> Result<(), ParseError> {
+        self.validate_section_line(line, value)?;
+        self.push_section_line(value);
+        self.parsing_items
+            .push(SectionItem::Text(value.to_string()));
+        Ok(())
+    }
+
+    fn front_matter_error(&self, line: &str, part: &str, message: &str) -> ParseError {
+        ParseError::new(
+            ParseErrorKind::InvalidFrontMatter(message.to_string()),
+            Some(SourceLocation::of_part(self.line_number, line, part)),
+        )
+    }
+}
@@ -1,22 +1,56 @@
-use crate::codelib::Commit;
+use crate::codelib::{
+    ArticleStatus, CodeBlock, Commit, ComplexityEntry, IncludeEntry, IncludeKind, LineRange,
+    UsageEntry,
+};
 use crate::{Article, CodeInfo};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::Read;
 
+mod code_info_python;
+mod code_info_ruby;
+mod code_info_rust;
+mod comment;
+mod complexity;
+mod error;
+mod format;
+mod front_matter;
+mod preprocessor;
+mod source_text;
+mod verified;
+use code_info_python::parse_code_info_python;
+use code_info_ruby::parse_code_info_ruby;
+use code_info_rust::parse_code_info_rust;
+use comment::CommentMatcher;
+use complexity::parse_complexity_entry;
+pub use error::{ParseError, ParseErrorKind, SourceLocation};
+pub use format::{diff_lines, format_document_from_str};
+use front_matter::is_front_matter_delimiter;
+use preprocessor::{logical_lines, split_directive, ConditionalTracker, LineState};
+pub use source_text::{decode_source, encode_source};
+use source_text::{normalize_source, read_source};
+pub use verified::parse_verified_url;
+
+#[cfg(test)]
+mod parser_code_info_unittest;
+#[cfg(test)]
+mod parser_comment_unittest;
+#[cfg(test)]
+mod parser_complexity_unittest;
+#[cfg(test)]
+mod parser_error_unittest;
+#[cfg(test)]
+mod parser_format_unittest;
+#[cfg(test)]
+mod parser_front_matter_unittest;
+#[cfg(test)]
+mod parser_preprocessor_unittest;
 #[cfg(test)]
 mod parser_snapshot_unittest;
-
-fn match_line_as_comment(line: &str) -> Option<&str> {
-    // 先頭が # で始まる行なら、先頭の # と後続するスペースを取り除いた文字列を返す
-    if line.starts_with("#") {
-        Some(line.trim_start_matches("#").trim_start())
-    } else if line.starts_with("//") {
-        Some(line.trim_start_matches("//").trim_start())
-    } else {
-        None
-    }
-}
+#[cfg(test)]
+mod parser_source_text_unittest;
+#[cfg(test)]
+mod parser_verified_unittest;
 
 fn match_block_anchor(comment: &str) -> Option<&str> {
     if comment.starts_with("%=") {
@@ -34,65 +68,420 @@ fn match_doc_anchor(comment: &str) -> Option<&str> {
     }
 }
 
+// %usage の各行を signature と、それに続く `;` から始まる説明に分ける
+// e.g.
+// void SparseTable::build()
+// ; クエリに応えられるように準備する．
+fn parse_usage_entries(text: &str) -> Vec<UsageEntry> {
+    let mut entries: Vec<UsageEntry> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(description) = line.strip_prefix(';') {
+            let description = description.trim();
+            match entries.last_mut() {
+                Some(entry) => {
+                    if !entry.description.is_empty() {
+                        entry.description.push('\n');
+                    }
+                    entry.description.push_str(description);
+                }
+                None => entries.push(UsageEntry {
+                    signature: String::new(),
+                    description: description.to_string(),
+                }),
+            }
+        } else {
+            entries.push(UsageEntry {
+                signature: line.to_string(),
+                description: String::new(),
+            });
+        }
+    }
+    entries
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum BlockAnchor {
     Code,
     Article,
 }
 
+// Locale of sections without suffix, e.g. `%title` is `%title.ja`.
+pub const SOURCE_LOCALE: &str = "ja";
+
+// Locale of `title`, `overview` and `usage` of articles unless configured.
+pub const DEFAULT_LOCALE: &str = SOURCE_LOCALE;
+
+// Title, overview and usage may have a locale suffix, e.g. `%title.en`.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 enum SectionAnchor {
-    Title,
-    Overview,
-    Usage,
+    Title(Option<String>),
+    Overview(Option<String>),
+    Usage(Option<String>),
+    Complexity,
     Require,
+    Example,
     Verified,
     References,
     Words,
+    Status,
+    Deprecated,
     Unknown(String),
 }
 
+fn is_locale(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 // Contert str to optional SectionAnchor
 impl SectionAnchor {
+    fn name(&self) -> String {
+        let (name, locale) = match self {
+            SectionAnchor::Title(locale) => ("title", locale),
+            SectionAnchor::Overview(locale) => ("overview", locale),
+            SectionAnchor::Usage(locale) => ("usage", locale),
+            SectionAnchor::Complexity => ("complexity", &None),
+            SectionAnchor::Require => ("require", &None),
+            SectionAnchor::Example => ("example", &None),
+            SectionAnchor::Verified => ("verified", &None),
+            SectionAnchor::References => ("references", &None),
+            SectionAnchor::Words => ("words", &None),
+            SectionAnchor::Status => ("status", &None),
+            SectionAnchor::Deprecated => ("deprecated", &None),
+            SectionAnchor::Unknown(name) => (name.as_str(), &None),
+        };
+        match locale {
+            Some(locale) => format!("{}.{}", name, locale),
+            None => name.to_string(),
+        }
+    }
+
     fn from_str(s: &str) -> Option<Self> {
+        if let Some((name, locale)) = s.split_once('.') {
+            if !is_locale(locale) {
+                return None;
+            }
+            let locale = Some(locale.to_string());
+            return match name {
+                "title" => Some(SectionAnchor::Title(locale)),
+                "overview" => Some(SectionAnchor::Overview(locale)),
+                "usage" => Some(SectionAnchor::Usage(locale)),
+                _ => None,
+            };
+        }
         match s {
-            "title" => Some(SectionAnchor::Title),
-            "overview" => Some(SectionAnchor::Overview),
-            "usage" => Some(SectionAnchor::Usage),
+            "title" => Some(SectionAnchor::Title(None)),
+            "overview" => Some(SectionAnchor::Overview(None)),
+            "usage" => Some(SectionAnchor::Usage(None)),
+            "complexity" => Some(SectionAnchor::Complexity),
             "require" => Some(SectionAnchor::Require),
+            "example" => Some(SectionAnchor::Example),
             "verified" => Some(SectionAnchor::Verified),
             "references" => Some(SectionAnchor::References),
             "words" => Some(SectionAnchor::Words),
+            "status" => Some(SectionAnchor::Status),
+            "deprecated" => Some(SectionAnchor::Deprecated),
             _ => None,
         }
     }
 }
 
+// Splits `BEGIN DOC name` into `BEGIN DOC` and `Some("name")`.
+fn split_block_anchor_argument(anchor: &str) -> (&str, Option<&str>) {
+    for keyword in ["BEGIN DOC", "BEGIN CODE"] {
+        if let Some(rest) = anchor.strip_prefix(keyword) {
+            if rest.starts_with(char::is_whitespace) {
+                return (keyword, Some(rest.trim()));
+            }
+        }
+    }
+    (anchor, None)
+}
+
+// Lines of a section as written, for the formatter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SectionItem {
+    Text(String), // comment body with its indent, see indented_comment
+    Fence { info: String, lines: Vec<String> },
+}
+
+// Comment body with the whitespace after the comment marker but the first
+// space, e.g. "  - nested" for `//   - nested`, so that nested lists and
+// indented text survive formatting. `comment` must be a substring slice of
+// `line`.
+fn indented_comment(line: &str, comment: &str) -> String {
+    let comment = comment.trim_end();
+    if comment.is_empty() {
+        return String::new();
+    }
+    let before = &line[..comment.as_ptr() as usize - line.as_ptr() as usize];
+    let indent = &before[before.trim_end().len()..];
+    format!("{}{}", indent.strip_prefix(' ').unwrap_or(indent), comment)
+}
+
+// Text in `locale`, falling back to the source locale and then any locale.
+fn localized_text(texts: &BTreeMap<String, String>, locale: &str) -> String {
+    texts
+        .get(locale)
+        .or_else(|| texts.get(SOURCE_LOCALE))
+        .or_else(|| texts.values().next())
+        .cloned()
+        .unwrap_or_default()
+}
+
+// A DOC block and following CODE blocks, which generate an article.
+struct ArticleFragment {
+    name: Option<String>,
+    collected_sections: BTreeMap<SectionAnchor, String>,
+    collected_code: String,
+    code_blocks: Vec<CodeBlock>,
+    complexity: Vec<ComplexityEntry>, // of the last %complexity, validated per line
+    status: Option<(ArticleStatus, SourceLocation)>,
+    doc_line_range: Option<LineRange>,
+    section_line_ranges: BTreeMap<String, LineRange>,
+    // None is the text before the first section
+    section_items: BTreeMap<Option<SectionAnchor>, Vec<SectionItem>>,
+    // for diagnostics
+    doc_location: Option<SourceLocation>,
+    code_location: Option<SourceLocation>,
+    stray_code_location: Option<SourceLocation>, // code in DOC block
+    duplicate_section_location: Option<SourceLocation>, // the second `%title` etc.
+    front_matter: bool,
+}
+
+impl ArticleFragment {
+    fn new() -> Self {
+        Self {
+            name: None,
+            collected_sections: BTreeMap::new(),
+            collected_code: String::new(),
+            code_blocks: Vec::new(),
+            complexity: Vec::new(),
+            status: None,
+            doc_line_range: None,
+            section_line_ranges: BTreeMap::new(),
+            section_items: BTreeMap::new(),
+            doc_location: None,
+            code_location: None,
+            stray_code_location: None,
+            duplicate_section_location: None,
+            front_matter: false,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.doc_location.is_none() && self.collected_code.is_empty()
+    }
+
+    fn generate_article(
+        self,
+        id: String,
+        path: String,
+        lang: String,
+        commits: Vec<Commit>,
+        tested_by: Vec<String>,
+        default_locale: &str,
+    ) -> Result<Article, ParseError> {
+        if self.collected_code.is_empty() {
+            return Err(ParseError::new(
+                ParseErrorKind::EmptyCode,
+                self.code_location.or(self.doc_location),
+            ));
+        }
+
+        let mut titles = BTreeMap::new();
+        let mut overviews = BTreeMap::new();
+        let mut usages = BTreeMap::new();
+        // a suffixed section comes after the unsuffixed one and overrides it
+        for (section, text) in &self.collected_sections {
+            let (map, locale) = match section {
+                SectionAnchor::Title(locale) => (&mut titles, locale),
+                SectionAnchor::Overview(locale) => (&mut overviews, locale),
+                SectionAnchor::Usage(locale) => (&mut usages, locale),
+                _ => continue,
+            };
+            let locale = locale.as_deref().unwrap_or(SOURCE_LOCALE);
+            map.insert(locale.to_string(), text.clone());
+        }
+
+        if titles.is_empty() {
+            return Err(ParseError::new(
+                ParseErrorKind::MissingTitle,
+                self.doc_location,
+            ));
+        }
+        let usage = localized_text(&usages, default_locale);
+
+        let replacement = self
+            .collected_sections
+            .get(&SectionAnchor::Deprecated)
+            .map(|s| s.trim());
+        let status = match (self.status, replacement) {
+            (None, None) => ArticleStatus::default(),
+            (None, Some(_)) => ArticleStatus::Deprecated,
+            (Some((status, _)), None) => status,
+            (Some((ArticleStatus::Deprecated, _)), Some(_)) => ArticleStatus::Deprecated,
+            (Some((_, location)), Some(_)) => {
+                return Err(ParseError::new(
+                    ParseErrorKind::ConflictingStatus,
+                    Some(location),
+                ));
+            }
+        };
+        // `src/a.hpp` and `/src/a.hpp` are the same
+        let replacement = replacement
+            .filter(|path| !path.is_empty())
+            .map(|path| format!("/{}", path.trim_start_matches('/')));
+
+        let verified: Vec<String> = self
+            .collected_sections
+            .get(&SectionAnchor::Verified)
+            .map(|s| s.split_whitespace().map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+
+        // DOC block exists because title exists
+        let doc_line_range = self.doc_line_range.unwrap();
+        // CODE block exists because code is not empty
+        let code_line_range = LineRange {
+            begin: self.code_blocks.first().unwrap().line_range.begin,
+            end: self.code_blocks.last().unwrap().line_range.end,
+        };
+
+        Ok(Article {
+            id,
+            title: localized_text(&titles, default_locale),
+            overview: localized_text(&overviews, default_locale),
+            usage_entries: parse_usage_entries(&usage),
+            usage,
+            localized_title: titles,
+            localized_overview: overviews,
+            localized_usage: usages,
+            complexity: self.complexity,
+            code: self.collected_code.trim().to_string(),
+            code_blocks: self
+                .code_blocks
+                .into_iter()
+                .map(|block| CodeBlock {
+                    // keep the indent of the first line
+                    code: block.code.trim_start_matches('\n').trim_end().to_string(),
+                    ..block
+                })
+                .collect(),
+            code_line_range,
+            lang,
+            path,
+            require: self
+                .collected_sections
+                .get(&SectionAnchor::Require)
+                .cloned()
+                .unwrap_or_default(),
+            example: self
+                .collected_sections
+                .get(&SectionAnchor::Example)
+                .cloned()
+                .unwrap_or_default(),
+            references: self
+                .collected_sections
+                .get(&SectionAnchor::References)
+                .map(|s| s.split_whitespace().map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
+            words: self
+                .collected_sections
+                .get(&SectionAnchor::Words)
+                .map(|s| {
+                    s.split([',', ' ', '\n', '\t'])
+                        .map(|s| s.to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            status,
+            replacement,
+            verified_entries: verified.iter().map(|url| parse_verified_url(url)).collect(),
+            verified,
+            extra: self
+                .collected_sections
+                .iter()
+                .filter_map(|(section, text)| match section {
+                    SectionAnchor::Unknown(name) => Some((name.clone(), text.clone())),
+                    _ => None,
+                })
+                .collect(),
+            doc_line_range,
+            section_line_ranges: self.section_line_ranges,
+            commits,
+            tested_by,
+            // filled by complete_articles, which knows macros in tests
+            conditionally_tested_by: Vec::new(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DocSyntax {
+    Anchor,      // %=BEGIN DOC ... %=END DOC
+    FrontMatter, // --- ... ---
+}
+
 struct ParserInternalState {
+    comment_matcher: CommentMatcher,
+    doc_syntax: Option<DocSyntax>, // selected by the first DOC block or `%=FRONT MATTER`
+    front_matter_column: usize,    // byte offset of keys in the line
+    front_matter_list: bool,       // `- ` of following lines is a list marker
     block_anchor: Option<BlockAnchor>,
     section_anchor: Option<SectionAnchor>,
+    section_line_range: LineRange, // from the anchor to the last non-empty line
     parsing_text: String,
-    collected_sections: BTreeMap<SectionAnchor, String>,
-    collected_code: String,
+    parsing_items: Vec<SectionItem>,
     section_codeblock: bool,
+    fragment: ArticleFragment,
+    finished_fragments: Vec<ArticleFragment>,
+    // for diagnostics
+    line_number: usize,
+    block_location: Option<SourceLocation>,
+    codeblock_location: Option<SourceLocation>,
 }
 
 impl ParserInternalState {
-    fn new() -> Self {
+    fn new(lang: &str) -> Self {
         Self {
+            comment_matcher: CommentMatcher::new(lang),
+            doc_syntax: None,
+            front_matter_column: 0,
+            front_matter_list: false,
             block_anchor: None,
             section_anchor: None,
+            section_line_range: LineRange { begin: 0, end: 0 },
             parsing_text: String::new(),
-            collected_sections: BTreeMap::new(),
-            collected_code: String::new(),
+            parsing_items: Vec::new(),
             section_codeblock: false,
+            fragment: ArticleFragment::new(),
+            finished_fragments: Vec::new(),
+            line_number: 0,
+            block_location: None,
+            codeblock_location: None,
         }
     }
 
     fn finish_anchor(&mut self) {
-        if let Some(section) = std::mem::replace(&mut self.section_anchor, None) {
+        let items = std::mem::take(&mut self.parsing_items);
+        if self.block_anchor == Some(BlockAnchor::Article)
+            && (self.section_anchor.is_some() || !items.is_empty())
+        {
+            self.fragment
+                .section_items
+                .insert(self.section_anchor.clone(), items);
+        }
+        if let Some(section) = self.section_anchor.take() {
+            self.fragment
+                .section_line_ranges
+                .insert(section.name(), self.section_line_range);
             let trimmed_text = self.parsing_text.trim();
-            self.collected_sections.insert(
+            self.fragment.collected_sections.insert(
                 section,
                 trimmed_text.to_string(), // no way to avoid clone here
             );
@@ -102,190 +491,558 @@ impl ParserInternalState {
         self.parsing_text.clear();
     }
 
-    fn parse_line(&mut self, line: &str) -> Result<(), String> {
-        if let Some(comment) = match_line_as_comment(line) {
+    fn start_section(&mut self, section: SectionAnchor) {
+        self.finish_anchor();
+        // entries are collected line by line, and only the last section is kept
+        if section == SectionAnchor::Complexity {
+            self.fragment.complexity.clear();
+        }
+        self.section_anchor = Some(section);
+        self.section_line_range = LineRange {
+            begin: self.line_number,
+            end: self.line_number,
+        };
+    }
+
+    fn push_section_line(&mut self, text: &str) {
+        if !text.trim().is_empty() {
+            self.section_line_range.end = self.line_number;
+        }
+        self.parsing_text.push_str(text);
+        self.parsing_text.push('\n');
+    }
+
+    // A new DOC block starts a new article; code before the first DOC block
+    // belongs to the first article.
+    fn finish_fragment(&mut self) {
+        let fragment = std::mem::replace(&mut self.fragment, ArticleFragment::new());
+        self.finished_fragments.push(fragment);
+    }
+
+    fn begin_doc(
+        &mut self,
+        name: Option<&str>,
+        location: SourceLocation,
+    ) -> Result<(), ParseError> {
+        if self.block_anchor.is_some() {
+            return Err(ParseError::new(
+                ParseErrorKind::NestedDocBlock,
+                Some(location),
+            ));
+        }
+        if self.fragment.doc_location.is_some() {
+            self.finish_fragment();
+        }
+        self.block_anchor = Some(BlockAnchor::Article);
+        self.fragment.name = name.map(|s| s.to_string());
+        self.fragment.doc_location = Some(location.clone());
+        self.fragment.doc_line_range = Some(LineRange {
+            begin: self.line_number,
+            end: self.line_number,
+        });
+        self.block_location = Some(location);
+        Ok(())
+    }
+
+    fn end_doc(&mut self) -> Result<(), ParseError> {
+        self.check_codeblock_closed()?;
+        self.finish_anchor();
+        if let Some(doc_line_range) = &mut self.fragment.doc_line_range {
+            doc_line_range.end = self.line_number;
+        }
+        self.block_anchor = None;
+        self.block_location = None;
+        Ok(())
+    }
+
+    fn begin_front_matter(
+        &mut self,
+        location: SourceLocation,
+        column: usize,
+    ) -> Result<(), ParseError> {
+        self.front_matter_column = column;
+        self.begin_doc(None, location)?;
+        self.fragment.front_matter = true;
+        Ok(())
+    }
+
+    // A file uses either `%=BEGIN DOC` or front matter, chosen by the first
+    // of them. Front matter must be enabled by `%=FRONT MATTER`, so that a
+    // `// ---` banner of an existing file is never read as front matter.
+    fn select_doc_syntax(
+        &mut self,
+        doc_syntax: DocSyntax,
+        location: SourceLocation,
+    ) -> Result<(), ParseError> {
+        if self
+            .doc_syntax
+            .is_some_and(|selected| selected != doc_syntax)
+        {
+            return Err(ParseError::new(
+                ParseErrorKind::MixedDocSyntax,
+                Some(location),
+            ));
+        }
+        self.doc_syntax = Some(doc_syntax);
+        Ok(())
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), ParseError> {
+        self.line_number += 1;
+        let comment = self.comment_matcher.match_line(line);
+        if let Some(comment) = comment {
+            if self.doc_syntax == Some(DocSyntax::FrontMatter) {
+                match self.block_anchor {
+                    Some(BlockAnchor::Article) => {
+                        return self.parse_front_matter_line(line, comment)
+                    }
+                    None if is_front_matter_delimiter(comment) => {
+                        let location =
+                            SourceLocation::of_part(self.line_number, line, comment.trim_end());
+                        let column = comment.as_ptr() as usize - line.as_ptr() as usize;
+                        return self.begin_front_matter(location, column);
+                    }
+                    _ => {}
+                }
+            }
             if let Some(anchor_str) = match_block_anchor(comment) {
-                match anchor_str {
-                    "BEGIN DOC" => {
-                        if let Some(_) = self.block_anchor {
-                            return Err("Nested block anchor is not allowed".to_string());
-                        }
-                        self.block_anchor = Some(BlockAnchor::Article);
+                let location = SourceLocation::of_part(self.line_number, line, anchor_str);
+                match split_block_anchor_argument(anchor_str) {
+                    ("END DOC", _) if self.doc_syntax == Some(DocSyntax::FrontMatter) => {
+                        return Err(ParseError::new(
+                            ParseErrorKind::MixedDocSyntax,
+                            Some(location),
+                        ));
                     }
-                    "BEGIN CODE" => {
-                        self.block_anchor = Some(BlockAnchor::Code);
+                    ("BEGIN DOC", name) => {
+                        self.select_doc_syntax(DocSyntax::Anchor, location.clone())?;
+                        self.begin_doc(name, location)?
                     }
-                    "END DOC" => {
-                        self.finish_anchor();
-                        self.block_anchor = None;
+                    ("FRONT MATTER", None) => {
+                        self.select_doc_syntax(DocSyntax::FrontMatter, location)?
+                    }
+                    ("BEGIN CODE", name) => {
+                        if self.block_anchor.is_some() {
+                            return Err(ParseError::new(
+                                ParseErrorKind::NestedCodeBlock,
+                                Some(location),
+                            ));
+                        }
+                        if let Some(name) = name {
+                            if self
+                                .fragment
+                                .code_blocks
+                                .iter()
+                                .any(|block| block.name.as_deref() == Some(name))
+                            {
+                                return Err(ParseError::new(
+                                    ParseErrorKind::DuplicateCodeBlockName(name.to_string()),
+                                    Some(location),
+                                ));
+                            }
+                        }
+                        self.block_anchor = Some(BlockAnchor::Code);
+                        self.fragment.code_blocks.push(CodeBlock {
+                            name: name.map(|s| s.to_string()),
+                            code: String::new(),
+                            line_range: LineRange {
+                                begin: self.line_number + 1,
+                                end: self.line_number,
+                            },
+                        });
+                        self.fragment.code_location.get_or_insert(location.clone());
+                        self.block_location = Some(location);
                     }
-                    "END CODE" => {
+                    ("END DOC", None) => self.end_doc()?,
+                    ("END CODE", None) => {
+                        if let Some(code_block) = self.fragment.code_blocks.last_mut() {
+                            code_block.line_range.end = self.line_number - 1;
+                        }
                         self.finish_anchor();
                         self.block_anchor = None;
+                        self.block_location = None;
                     }
                     _ => {
-                        return Err(format!("Unknown block anchor: {}", anchor_str));
+                        return Err(ParseError::new(
+                            ParseErrorKind::UnknownBlockAnchor(anchor_str.to_string()),
+                            Some(location),
+                        ));
                     }
                 }
+            } else if let Some(BlockAnchor::Code) = self.block_anchor {
+                // comments in the code are a part of the code
+                self.push_code_line(line);
             } else if let Some(anchor) = match_doc_anchor(comment) {
                 // Article ブロックのみ有効
                 if let Some(BlockAnchor::Article) = self.block_anchor {
-                    if let Some(section) = SectionAnchor::from_str(anchor) {
-                        self.finish_anchor();
-                        self.section_anchor = Some(section);
-                    } else {
-                        self.finish_anchor();
-                        self.section_anchor = Some(SectionAnchor::Unknown(anchor.to_string()));
+                    let section = SectionAnchor::from_str(anchor)
+                        .unwrap_or_else(|| SectionAnchor::Unknown(anchor.to_string()));
+                    // only the last one is kept
+                    let duplicate = self.section_anchor.as_ref() == Some(&section)
+                        || self
+                            .fragment
+                            .section_items
+                            .contains_key(&Some(section.clone()));
+                    if duplicate {
+                        self.fragment
+                            .duplicate_section_location
+                            .get_or_insert_with(|| {
+                                SourceLocation::of_part(self.line_number, line, comment.trim_end())
+                            });
                     }
+                    self.start_section(section);
                 }
-            } else if comment.starts_with("```") {
+            } else if let Some(info) = comment.strip_prefix("```") {
+                self.section_codeblock = !self.section_codeblock;
                 if self.section_codeblock {
-                    self.section_codeblock = false;
-                } else {
-                    self.section_codeblock = true;
+                    self.parsing_items.push(SectionItem::Fence {
+                        info: info.trim().to_string(),
+                        lines: Vec::new(),
+                    });
                 }
+                if self.section_anchor.is_some() {
+                    self.section_line_range.end = self.line_number;
+                }
+                self.codeblock_location = if self.section_codeblock {
+                    Some(SourceLocation::of_part(self.line_number, line, comment))
+                } else {
+                    None
+                };
             } else {
                 if self.section_codeblock {
-                    self.parsing_text.push_str(line);
-                    self.parsing_text.push_str("\n");
+                    self.push_fenced_line(line);
                 } else {
-                    self.parsing_text.push_str(comment);
-                    self.parsing_text.push_str("\n");
+                    self.validate_section_line(line, comment)?;
+                    self.push_section_line(comment);
+                    self.parsing_items
+                        .push(SectionItem::Text(indented_comment(line, comment)));
                 }
             }
         } else {
             if let Some(BlockAnchor::Code) = self.block_anchor {
-                self.collected_code.push_str(line);
-                self.collected_code.push_str("\n");
+                self.push_code_line(line);
             } else if self.section_codeblock {
-                self.parsing_text.push_str(line);
-                self.parsing_text.push_str("\n");
+                self.push_fenced_line(line);
             } else {
                 // ignore
+                if self.block_anchor == Some(BlockAnchor::Article) && !line.trim().is_empty() {
+                    self.fragment.stray_code_location.get_or_insert_with(|| {
+                        SourceLocation::of_part(self.line_number, line, line.trim())
+                    });
+                }
             }
         }
         Ok(())
     }
 
-    fn generate_article(
-        self,
-        path: String,
-        lang: String,
-        commits: Vec<Commit>,
-        tested_by: Vec<String>,
-    ) -> Result<Article, String> {
-        if let Some(_) = self.block_anchor {
-            return Err("Block anchor is not closed".to_string());
+    fn push_fenced_line(&mut self, line: &str) {
+        self.push_section_line(line);
+        if let Some(SectionItem::Fence { lines, .. }) = self.parsing_items.last_mut() {
+            lines.push(line.to_string());
         }
+    }
 
-        if self.collected_code.is_empty() {
-            return Err("Code block is empty".to_string());
+    // Sections parsed line by line for diagnostics.
+    // `comment` must be a substring slice of `line`.
+    fn validate_section_line(&mut self, line: &str, comment: &str) -> Result<(), ParseError> {
+        match self.section_anchor {
+            Some(SectionAnchor::Complexity) => self.push_complexity_line(line, comment),
+            Some(SectionAnchor::Status) => self.push_status_line(line, comment),
+            _ => Ok(()),
         }
+    }
 
-        if self.collected_sections.get(&SectionAnchor::Title).is_none() {
-            return Err("Title is required".to_string());
+    fn push_status_line(&mut self, line: &str, comment: &str) -> Result<(), ParseError> {
+        let status = comment.trim();
+        if status.is_empty() {
+            return Ok(());
         }
+        let location = SourceLocation::of_part(self.line_number, line, status);
+        let status = match status {
+            "experimental" => ArticleStatus::Experimental,
+            "stable" => ArticleStatus::Stable,
+            "deprecated" => ArticleStatus::Deprecated,
+            _ => {
+                return Err(ParseError::new(
+                    ParseErrorKind::InvalidStatus(status.to_string()),
+                    Some(location),
+                ))
+            }
+        };
+        self.fragment.status = Some((status, location));
+        Ok(())
+    }
 
-        Ok(Article {
-            title: self
-                .collected_sections
-                .get(&SectionAnchor::Title)
-                .unwrap_or(&String::new())
-                .clone(),
-            overview: self
-                .collected_sections
-                .get(&SectionAnchor::Overview)
-                .unwrap_or(&String::new())
-                .clone(),
-            code: self.collected_code.trim().to_string(),
-            lang,
-            path,
-            require: self
-                .collected_sections
-                .get(&SectionAnchor::Require)
-                .map(|s| s.clone()).unwrap_or(String::new()),
-            references: self
-                .collected_sections
-                .get(&SectionAnchor::References)
-                .map(|s| s.split_whitespace().map(|s| s.to_string()).collect())
-                .unwrap_or(Vec::new()),
-            words: self
-                .collected_sections
-                .get(&SectionAnchor::Words)
-                .map(|s| {
-                    s.split([',', ' ', '\n', '\t'])
-                        .map(|s| s.to_string())
-                        .collect()
-                })
-                .unwrap_or(Vec::new()),
-            verified: self
-                .collected_sections
-                .get(&SectionAnchor::Verified)
-                .map(|s| s.split_whitespace().map(|s| s.to_string()).collect())
-                .unwrap_or(Vec::new()),
-            commits,
-            tested_by,
-        })
+    fn push_complexity_line(&mut self, line: &str, comment: &str) -> Result<(), ParseError> {
+        if comment.trim().is_empty() {
+            return Ok(());
+        }
+        match parse_complexity_entry(comment) {
+            Ok(entry) => {
+                self.fragment.complexity.push(entry);
+                Ok(())
+            }
+            Err(err) => Err(ParseError::new(
+                ParseErrorKind::InvalidComplexity(err.message),
+                Some(SourceLocation::of_part(
+                    self.line_number,
+                    line,
+                    &comment[err.span],
+                )),
+            )),
+        }
+    }
+
+    fn push_code_line(&mut self, line: &str) {
+        self.fragment.collected_code.push_str(line);
+        self.fragment.collected_code.push('\n');
+        if let Some(code_block) = self.fragment.code_blocks.last_mut() {
+            code_block.code.push_str(line);
+            code_block.code.push('\n');
+        }
+    }
+
+    fn check_codeblock_closed(&self) -> Result<(), ParseError> {
+        if self.section_codeblock {
+            return Err(ParseError::new(
+                ParseErrorKind::UnterminatedCodeFence,
+                self.codeblock_location.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    // Called after all lines are parsed.
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.check_codeblock_closed()?;
+        self.finish_anchor();
+        if self.block_anchor.is_some() {
+            return Err(ParseError::new(
+                ParseErrorKind::UnclosedBlock,
+                self.block_location.take(),
+            ));
+        }
+        // a file without DOC blocks is reported as an article without code
+        if !self.fragment.is_empty() || self.finished_fragments.is_empty() {
+            self.finish_fragment();
+        }
+        Ok(())
+    }
+
+    // Articles are identified by `path` if the file has only one unnamed
+    // article, otherwise by `path#name` (`path#1`, `path#2`, ... if unnamed).
+    fn generate_articles(
+        self,
+        path: String,
+        lang: String,
+        commits: Vec<Commit>,
+        tested_by: Vec<String>,
+        default_locale: &str,
+    ) -> Result<Vec<Article>, ParseError> {
+        let single = self.finished_fragments.len() == 1;
+        let mut names = BTreeSet::new();
+        let mut articles = Vec::new();
+        for (i, fragment) in self.finished_fragments.into_iter().enumerate() {
+            let id = match &fragment.name {
+                Some(name) => {
+                    if !names.insert(name.clone()) {
+                        return Err(ParseError::new(
+                            ParseErrorKind::DuplicateArticleName(name.clone()),
+                            fragment.doc_location,
+                        ));
+                    }
+                    format!("{}#{}", path, name)
+                }
+                None if single => path.clone(),
+                None => format!("{}#{}", path, i + 1),
+            };
+            articles.push(fragment.generate_article(
+                id,
+                path.clone(),
+                lang.clone(),
+                commits.clone(),
+                tested_by.clone(),
+                default_locale,
+            )?);
+        }
+        Ok(articles)
     }
 }
 
-fn parse_code_info_from_file_cpp(file: File) -> Result<CodeInfo, String> {
-    let reader = BufReader::new(file);
+// `#include` and `#define` in `#if 0` or comments are ignored. Includes in
+// `#ifdef` and so on are tagged as conditional.
+fn parse_code_info_cpp(text: &str) -> Result<CodeInfo, ParseError> {
     let mut filepath_dependencies = Vec::new();
-    for may_line in reader.lines() {
-        let line = match may_line {
-            Ok(line) => line,
-            Err(_) => continue,
+    let mut conditional_dependencies = Vec::new();
+    let mut includes = Vec::new();
+    let mut problem = None;
+    let mut ignore = false;
+    let mut error = None;
+    // unwrap is ok because the pattern is always valid
+    let re = regex::Regex::new(r#"^(?:"([^"]*)"|<([^>]*)>)"#).unwrap();
+    let re_define = regex::Regex::new(r#"^(PROBLEM|IGNORE|ERROR)\b\s*(.*)$"#).unwrap();
+    let mut tracker = ConditionalTracker::new();
+    for line in logical_lines(text) {
+        let Some((name, args)) = split_directive(&line.text) else {
+            continue;
         };
-        // Is this line a `#include` directive?
-        if line.starts_with("#include") {
-            // Extract the path from the `#include` directive by regexp
-            // <path> will be ignored.
-            // unwrap is ok because the pattern is always valid
-            let re = regex::Regex::new(r#"#include\s*["](.*)["]"#).unwrap();
-            let captures = re.captures(&line);
-            if let Some(captures) = captures {
-                let path = captures.get(1).unwrap().as_str();
-                // TODO: normalize path?
-                filepath_dependencies.push(format!("/{}", path));
+        let state = tracker.state();
+        tracker.directive(name, args);
+        if state == LineState::Inactive {
+            continue;
+        }
+        let conditional = state == LineState::Conditional;
+        match name {
+            // online-judge-verify-helper markers
+            "define" => {
+                if let Some(captures) = re_define.captures(args) {
+                    let value = captures.get(2).unwrap().as_str().trim().trim_matches('"');
+                    match captures.get(1).unwrap().as_str() {
+                        "PROBLEM" => problem = Some(value.to_string()),
+                        "IGNORE" => ignore = true,
+                        "ERROR" => error = Some(value.to_string()),
+                        _ => unreachable!(),
+                    }
+                }
             }
+            "include" => {
+                let Some(captures) = re.captures(args) else {
+                    continue;
+                };
+                if let Some(path) = captures.get(1) {
+                    let path = path.as_str();
+                    // relative to the repository root until resolved by
+                    // IncludeResolver, which knows the path of this file
+                    if conditional {
+                        conditional_dependencies.push(format!("/{}", path));
+                    } else {
+                        filepath_dependencies.push(format!("/{}", path));
+                    }
+                    includes.push(IncludeEntry {
+                        include: path.to_string(),
+                        kind: IncludeKind::Quote,
+                        conditional,
+                        resolved: None,
+                    });
+                } else if let Some(path) = captures.get(2) {
+                    // a system header unless resolved by IncludeResolver
+                    includes.push(IncludeEntry {
+                        include: path.as_str().to_string(),
+                        kind: IncludeKind::Angle,
+                        conditional,
+                        resolved: None,
+                    });
+                }
+            }
+            _ => {}
         }
     }
     Ok(CodeInfo {
         filepath_dependencies,
+        conditional_dependencies,
+        includes,
+        system_headers: Vec::new(),
+        problem,
+        ignore,
+        error,
     })
 }
 
 // ----------------------------------------------------------------------------
 
-pub fn parse_document_from_file(
-    file: File,
+// Lines are split by LF, CRLF or CR. BOM is ignored.
+pub fn parse_document_from_str(
+    text: &str,
+    article_path: String,
+    lang: String,
+    commits: Vec<Commit>,
+    tested_by: Vec<String>,
+) -> Result<Vec<Article>, ParseError> {
+    parse_document_from_str_with_locale(
+        text,
+        article_path,
+        lang,
+        commits,
+        tested_by,
+        DEFAULT_LOCALE,
+    )
+}
+
+// Sections without locale suffix are in SOURCE_LOCALE. `default_locale` only
+// chooses the locale of `title`, `overview` and `usage` of the article.
+pub fn parse_document_from_str_with_locale(
+    text: &str,
     article_path: String,
     lang: String,
     commits: Vec<Commit>,
     tested_by: Vec<String>,
-) -> Result<Article, String> {
-    let reader = BufReader::new(file);
+    default_locale: &str,
+) -> Result<Vec<Article>, ParseError> {
+    let text = normalize_source(text);
 
-    let mut parser_state = ParserInternalState::new();
+    let mut parser_state = ParserInternalState::new(&lang);
 
-    for may_line in reader.lines() {
-        if let Ok(line) = may_line {
-            if let Err(err) = parser_state.parse_line(&line) {
-                return Err(err);
-            }
-        }
+    for line in text.lines() {
+        parser_state
+            .parse_line(line)
+            .map_err(|err| err.with_path(&article_path))?;
     }
-    parser_state.finish_anchor();
+    parser_state
+        .finish()
+        .map_err(|err| err.with_path(&article_path))?;
+
+    let path = article_path.clone();
+    parser_state
+        .generate_articles(article_path, lang, commits, tested_by, default_locale)
+        .map_err(|err| err.with_path(&path))
+}
+
+// The input must be UTF-8 (or UTF-16 with BOM). Use `decode_source` and
+// `parse_document_from_str` for other encodings.
+pub fn parse_document_from_reader<R: Read>(
+    reader: R,
+    article_path: String,
+    lang: String,
+    commits: Vec<Commit>,
+    tested_by: Vec<String>,
+) -> Result<Vec<Article>, ParseError> {
+    let text = read_source(reader).map_err(|err| err.with_path(&article_path))?;
+    parse_document_from_str(&text, article_path, lang, commits, tested_by)
+}
 
-    parser_state.generate_article(article_path, lang, commits, tested_by)
+pub fn parse_document_from_file(
+    file: File,
+    article_path: String,
+    lang: String,
+    commits: Vec<Commit>,
+    tested_by: Vec<String>,
+) -> Result<Vec<Article>, ParseError> {
+    parse_document_from_reader(file, article_path, lang, commits, tested_by)
 }
 
-pub fn parse_code_info_from_file(file: File, lang: String) -> Result<CodeInfo, String> {
+// `filepath_dependencies` of cpp are `#include "..."` taken as relative to the
+// repository root, e.g. "/src/cpp/a.hpp". Those of rust, ruby and python are
+// empty because their paths depend on the path of the file; all of them are
+// replaced by IncludeResolver::resolve_code_info using `includes`.
+pub fn parse_code_info_from_str(text: &str, lang: String) -> Result<CodeInfo, ParseError> {
+    let text = normalize_source(text);
     match lang.as_str() {
-        "cpp" => parse_code_info_from_file_cpp(file),
-        _ => Ok(CodeInfo {
-            filepath_dependencies: Vec::new(),
-        }),
+        "cpp" => parse_code_info_cpp(&text),
+        "rust" => parse_code_info_rust(&text),
+        "ruby" => parse_code_info_ruby(&text),
+        "python" => parse_code_info_python(&text),
+        _ => Ok(CodeInfo::default()),
     }
 }
+
+pub fn parse_code_info_from_reader<R: Read>(
+    reader: R,
+    lang: String,
+) -> Result<CodeInfo, ParseError> {
+    let text = read_source(reader)?;
+    parse_code_info_from_str(&text, lang)
+}
+
+pub fn parse_code_info_from_file(file: File, lang: String) -> Result<CodeInfo, ParseError> {
+    parse_code_info_from_reader(file, lang)
+}
@@ -0,0 +1,272 @@
+use super::parse_code_info_from_str;
+use crate::codelib::{IncludeEntry, IncludeKind};
+
+#[test]
+fn test_cpp_include() {
+    let code_info = parse_code_info_from_str(
+        r#"#include <vector>
+#include "src/cpp/container/likermq/rmq_sparsetable.hpp"
+int main() {}
+"#,
+        "cpp".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        code_info.filepath_dependencies,
+        vec!["/src/cpp/container/likermq/rmq_sparsetable.hpp".to_string()]
+    );
+    assert_eq!(
+        code_info.includes,
+        vec![
+            IncludeEntry {
+                include: "vector".to_string(),
+                kind: IncludeKind::Angle,
+                conditional: false,
+                resolved: None,
+            },
+            IncludeEntry {
+                include: "src/cpp/container/likermq/rmq_sparsetable.hpp".to_string(),
+                kind: IncludeKind::Quote,
+                conditional: false,
+                resolved: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_lang() {
+    let code_info = parse_code_info_from_str("require 'set'\n", "unknown".to_string()).unwrap();
+    assert!(code_info.filepath_dependencies.is_empty());
+}
+
+#[test]
+fn test_cpp_verify_helper_markers() {
+    let code_info = parse_code_info_from_str(
+        r#"#define PROBLEM "https://judge.yosupo.jp/problem/staticrmq"
+#define ERROR "1e-6"
+#include "src/cpp/container/likermq/rmq_sparsetable.hpp"
+"#,
+        "cpp".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        code_info.problem,
+        Some("https://judge.yosupo.jp/problem/staticrmq".to_string())
+    );
+    assert_eq!(code_info.error, Some("1e-6".to_string()));
+    assert!(!code_info.ignore);
+
+    let code_info =
+        parse_code_info_from_str("#  define IGNORE\nint main() {}\n", "cpp".to_string()).unwrap();
+    assert!(code_info.ignore);
+    assert_eq!(code_info.problem, None);
+}
+
+#[test]
+fn test_cpp_preprocessor() {
+    let code_info = parse_code_info_from_str(
+        r#"#ifndef A_HPP
+#define A_HPP
+#  include "src/cpp/a.hpp"
+#include \
+    "src/cpp/b.hpp"
+// #include "src/cpp/commented.hpp"
+/*
+#include "src/cpp/commented.hpp"
+*/
+#if 0
+#include "src/cpp/disabled.hpp"
+#define PROBLEM "https://example.com/disabled"
+#endif
+#ifdef USE_C
+#include "src/cpp/c.hpp"
+#include <c.hpp>
+#endif
+#endif
+"#,
+        "cpp".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        code_info.filepath_dependencies,
+        vec!["/src/cpp/a.hpp".to_string(), "/src/cpp/b.hpp".to_string()]
+    );
+    assert_eq!(
+        code_info.conditional_dependencies,
+        vec!["/src/cpp/c.hpp".to_string()]
+    );
+    assert_eq!(
+        code_info
+            .includes
+            .iter()
+            .map(|include| (include.include.as_str(), include.conditional))
+            .collect::<Vec<_>>(),
+        vec![
+            ("src/cpp/a.hpp", false),
+            ("src/cpp/b.hpp", false),
+            ("src/cpp/c.hpp", true),
+            ("c.hpp", true),
+        ]
+    );
+    assert_eq!(code_info.problem, None);
+}
+
+#[test]
+fn test_rust_modules() {
+    let code_info = parse_code_info_from_str(
+        r#"//! mod doc_comment;
+pub mod graph;
+#[cfg(test)]
+mod tests;
+#[path = "../util/bits.rs"]
+pub(crate) mod bits;
+/*
+mod commented;
+*/
+use std::collections::BTreeMap;
+use crate::graph::{
+    dijkstra::{self, Dijkstra},
+    bfs as breadth_first, // comment
+};
+use codelib::math::*;
+include!("generated.rs");
+fn main() {
+    let url = "http://example.com"; // mod x;
+}
+"#,
+        "rust".to_string(),
+    )
+    .unwrap();
+    assert!(code_info.filepath_dependencies.is_empty());
+    assert_eq!(
+        code_info
+            .includes
+            .iter()
+            .map(|include| (include.include.as_str(), include.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            ("graph", IncludeKind::RustMod),
+            ("tests", IncludeKind::RustMod),
+            ("../util/bits.rs", IncludeKind::Quote),
+            ("std::collections::BTreeMap", IncludeKind::RustUse),
+            ("crate::graph::dijkstra", IncludeKind::RustUse),
+            ("crate::graph::dijkstra::Dijkstra", IncludeKind::RustUse),
+            ("crate::graph::bfs", IncludeKind::RustUse),
+            ("codelib::math", IncludeKind::RustUse),
+            ("generated.rs", IncludeKind::Quote),
+        ]
+    );
+}
+
+#[test]
+fn test_ruby_require() {
+    let code_info = parse_code_info_from_str(
+        r#"# require_relative 'commented'
+=begin
+require_relative 'commented'
+=end
+require 'set'
+require_relative '../graph/dijkstra'
+require_relative("util.rb")
+puts "require 'x'"
+"#,
+        "ruby".to_string(),
+    )
+    .unwrap();
+    assert!(code_info.filepath_dependencies.is_empty());
+    assert_eq!(
+        code_info
+            .includes
+            .iter()
+            .map(|include| (include.include.as_str(), include.kind))
+            .collect::<Vec<_>>(),
+        vec![
+            ("set.rb", IncludeKind::Angle),
+            ("../graph/dijkstra.rb", IncludeKind::Quote),
+            ("util.rb", IncludeKind::Quote),
+        ]
+    );
+}
+
+#[test]
+fn test_python_import() {
+    let code_info = parse_code_info_from_str(
+        r#""""
+import commented
+"""
+import os, codelib.graph.dijkstra as dijkstra  # comment
+from .util import gcd, lcm
+from .. import tree
+from ..segtree.lazy import (
+    LazySegTree,
+    op,
+)
+from . import *
+try:
+    import numpy
+except ImportError:
+    pass
+print("(")
+import codelib.graph
+"#,
+        "python".to_string(),
+    )
+    .unwrap();
+    assert!(code_info.filepath_dependencies.is_empty());
+    assert!(code_info
+        .includes
+        .iter()
+        .all(|include| include.kind == IncludeKind::PythonImport));
+    assert_eq!(
+        code_info
+            .includes
+            .iter()
+            .map(|include| include.include.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "os",
+            "codelib",
+            "codelib.graph",
+            "codelib.graph.dijkstra",
+            ".util",
+            ".util.gcd",
+            ".util.lcm",
+            "..",
+            "..tree",
+            "..segtree",
+            "..segtree.lazy",
+            "..segtree.lazy.LazySegTree",
+            "..segtree.lazy.op",
+            ".",
+            "numpy",
+        ]
+    );
+}
+
+#[test]
+fn test_python_import_in_string() {
+    let code_info = parse_code_info_from_str(
+        r#"def f():
+    """Docstring.
+
+    import fake
+    """
+    import inner
+s = """
+from fake2 import x
+"""
+import real  # "comment
+"#,
+        "python".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        code_info
+            .includes
+            .iter()
+            .map(|include| include.include.as_str())
+            .collect::<Vec<_>>(),
+        vec!["inner", "real"]
+    );
+}
@@ -0,0 +1,169 @@
+use super::comment::CommentMatcher;
+
+fn match_lines(lang: &str, lines: &[&str]) -> Vec<Option<String>> {
+    let mut matcher = CommentMatcher::new(lang);
+    lines
+        .iter()
+        .map(|line| matcher.match_line(line).map(|s| s.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_cpp() {
+    assert_eq!(
+        match_lines(
+            "cpp",
+            &[
+                "// %title",
+                "#include <vector>",
+                "/* %overview",
+                " * a",
+                " */",
+                "/** b */",
+                "int a;",
+            ]
+        ),
+        vec![
+            Some("%title".to_string()),
+            None,
+            Some("%overview".to_string()),
+            Some("a".to_string()),
+            Some("".to_string()),
+            Some("b".to_string()),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_rust() {
+    assert_eq!(
+        match_lines("rust", &["/// %title", "//! a", "// b", "fn f() {}"]),
+        vec![
+            Some("%title".to_string()),
+            Some("a".to_string()),
+            Some("b".to_string()),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_ruby() {
+    assert_eq!(
+        match_lines(
+            "ruby",
+            &["# %title", "=begin", "%overview", "=end", "a = 1"]
+        ),
+        vec![
+            Some("%title".to_string()),
+            Some("".to_string()),
+            Some("%overview".to_string()),
+            Some("".to_string()),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_python() {
+    assert_eq!(
+        match_lines(
+            "python",
+            &["\"\"\"", "%title", "\"\"\"", "'''a'''", "x = 1"]
+        ),
+        vec![
+            Some("".to_string()),
+            Some("%title".to_string()),
+            Some("".to_string()),
+            Some("a".to_string()),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_haskell_and_lisp() {
+    assert_eq!(
+        match_lines("haskell", &["-- %title", "{- a -}", "main = pure ()"]),
+        vec![Some("%title".to_string()), Some("a".to_string()), None]
+    );
+    assert_eq!(
+        match_lines("lisp", &[";;; %title", "(defun f ())"]),
+        vec![Some("%title".to_string()), None]
+    );
+}
+
+#[test]
+fn test_indented_comment() {
+    assert_eq!(
+        match_lines("cpp", &["  // %title", "\t/* a */", "    int a;  // b"]),
+        vec![Some("%title".to_string()), Some("a".to_string()), None]
+    );
+}
+
+#[test]
+fn test_preprocessor_directive() {
+    assert_eq!(
+        match_lines(
+            "unknown",
+            &[
+                "#include <vector>",
+                "#define N 10",
+                "  #pragma once",
+                "# %title",
+                "#if"
+            ]
+        ),
+        vec![None, None, None, Some("%title".to_string()), None]
+    );
+}
+
+#[test]
+fn test_string_literal() {
+    assert_eq!(
+        match_lines(
+            "cpp",
+            &[
+                r#"const char* s = "//"; /* a"#,
+                "// b */",
+                r#"auto t = R"xx("#,
+                "// %title",
+                r#")xx"; char c = '"';"#,
+                "// c",
+                "int d = 1'000; // don't",
+                "// e",
+            ]
+        ),
+        vec![
+            None,
+            Some("// b".to_string()),
+            None,
+            None,
+            None,
+            Some("c".to_string()),
+            None,
+            Some("e".to_string()),
+        ]
+    );
+    assert_eq!(
+        match_lines(
+            "python",
+            &["s = \"\"\"", "# not a comment", "\"\"\"", "# a"]
+        ),
+        vec![None, None, None, Some("a".to_string())]
+    );
+    assert_eq!(
+        match_lines(
+            "rust",
+            &[
+                "fn f<'a>(s: &'a str) {",
+                "let s = r#\"",
+                "// x",
+                "\"#;",
+                "// y"
+            ]
+        ),
+        vec![None, None, None, None, Some("y".to_string())]
+    );
+}
@@ -0,0 +1,127 @@
+use super::complexity::parse_complexity_entry;
+use super::{parse_document_from_str, ParseErrorKind, ParserInternalState};
+use crate::codelib::ComplexityEntry;
+
+fn normalize(text: &str) -> String {
+    parse_complexity_entry(&format!("op: {}", text))
+        .unwrap_or_else(|err| panic!("{}: {}", text, err.message))
+        .time
+}
+
+#[test]
+fn test_entry() {
+    assert_eq!(
+        parse_complexity_entry("SparseTable::build: O(NlogN), O(N log N)").ok(),
+        Some(ComplexityEntry {
+            operation: "SparseTable::build".to_string(),
+            time: "O(N log N)".to_string(),
+            space: Some("O(N log N)".to_string()),
+        })
+    );
+    assert_eq!(
+        parse_complexity_entry("query: O(1)").ok(),
+        Some(ComplexityEntry {
+            operation: "query".to_string(),
+            time: "O(1)".to_string(),
+            space: None,
+        })
+    );
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("O(log^2N)"), "O(log^2 N)");
+    assert_eq!(normalize("O(log(N)^2)"), "O(log^2 N)");
+    assert_eq!(normalize("O( n log n )"), "O(n log n)");
+    assert_eq!(normalize("O(nlogn)"), "O(n log n)");
+    assert_eq!(normalize("O(N*M)"), "O(N M)");
+    assert_eq!(normalize("O((N+Q)logN)"), "O((N + Q) log N)");
+    assert_eq!(normalize("O(N^{1.5})"), "O(N^1.5)");
+    assert_eq!(normalize("O(N sqrt N)"), "O(N sqrt(N))");
+    assert_eq!(normalize("O(Nα(N))"), "O(N alpha(N))");
+    assert_eq!(normalize("O(min(N,M))"), "O(min(N, M))");
+    assert_eq!(normalize("O(N loglogN)"), "O(N log log N)");
+    assert_eq!(normalize("O(log_2 N)"), "O(log_2 N)");
+    assert_eq!(normalize("O(N/(M K))"), "O(N / (M K))");
+    assert_eq!(normalize("O(2^N N^2)"), "O(2^N N^2)");
+    assert_eq!(normalize("O(N!)"), "O(N!)");
+    assert_eq!(normalize("O(N ! N)"), "O(N! N)");
+    assert_eq!(normalize("O((N+1)!)"), "O((N + 1)!)");
+}
+
+#[test]
+fn test_invalid() {
+    for (text, span) in [
+        ("build O(N)", 0..10),
+        (": O(N)", 0..1),
+        ("build: N log N", 7..14),
+        ("build: O(N log)", 14..14),
+        ("build: O(N +)", 12..12),
+        ("build: O(N))", 10..11),
+        ("build: O(N % M)", 11..12),
+        ("build: O(!N)", 9..10),
+        ("build: O(N^)", 11..11),
+        ("build: O(min N)", 13..14),
+        ("build: O(N), M", 13..14),
+    ] {
+        let err = match parse_complexity_entry(text) {
+            Ok(entry) => panic!("{} is parsed as {:?}", text, entry),
+            Err(err) => err,
+        };
+        assert_eq!(err.span, span, "{}: {}", text, err.message);
+    }
+}
+
+#[test]
+fn test_location() {
+    let mut parser_state = ParserInternalState::new("cpp");
+    let lines = [
+        "// %=BEGIN DOC",
+        "// %complexity",
+        "// build: O(N)",
+        "// query: O(log N",
+    ];
+    let err = lines
+        .iter()
+        .try_for_each(|line| parser_state.parse_line(line))
+        .unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::InvalidComplexity("complexity must be written as `O(...)`".to_string())
+    );
+    let location = err.location.unwrap();
+    assert_eq!(location.line_number, 4);
+    assert_eq!(location.column, 11);
+    assert_eq!(location.width, 7);
+}
+
+#[test]
+fn test_last_section_is_kept() {
+    let text = "\
+// %=BEGIN DOC
+// %title
+// a
+// %complexity
+// build: O(N)
+// %complexity
+// query: O(1)
+// %=END DOC
+// %=BEGIN CODE
+int a;
+// %=END CODE
+";
+    let articles = parse_document_from_str(
+        text,
+        "/src/cpp/a.hpp".to_string(),
+        "cpp".to_string(),
+        vec![],
+        vec![],
+    )
+    .unwrap();
+    let operations: Vec<&str> = articles[0]
+        .complexity
+        .iter()
+        .map(|entry| entry.operation.as_str())
+        .collect();
+    assert_eq!(operations, vec!["query"]);
+}
@@ -0,0 +1,180 @@
+use super::{ParseError, ParseErrorKind, ParserInternalState};
+
+fn parse_lines(lines: &[&str]) -> Result<(), ParseError> {
+    let mut parser_state = ParserInternalState::new("cpp");
+    for line in lines {
+        parser_state.parse_line(line)?;
+    }
+    parser_state.finish()?;
+    parser_state
+        .generate_articles(
+            "/src/cpp/a.hpp".to_string(),
+            "cpp".to_string(),
+            vec![],
+            vec![],
+            "ja",
+        )
+        .map(|_| ())
+}
+
+#[test]
+fn test_unknown_block_anchor() {
+    let err = parse_lines(&["// %=BEGIN DOC", "// %=BEGIN FOO"]).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::UnknownBlockAnchor("BEGIN FOO".to_string())
+    );
+    let location = err.location.as_ref().unwrap();
+    assert_eq!(location.line_number, 2);
+    assert_eq!(location.column, 6);
+
+    let rendered = format!("{}", err.with_path("/src/cpp/a.hpp"));
+    assert_eq!(
+        rendered,
+        "error: unknown block anchor `BEGIN FOO`\n  --> /src/cpp/a.hpp:2:6\n  |\n2 | // %=BEGIN FOO\n  |      ^^^^^^^^^"
+    );
+}
+
+#[test]
+fn test_nested_doc_block() {
+    let err = parse_lines(&["// %=BEGIN DOC", "// %=BEGIN DOC"]).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::NestedDocBlock);
+    assert_eq!(err.location.unwrap().line_number, 2);
+}
+
+#[test]
+fn test_unclosed_block() {
+    let err = parse_lines(&["// %=BEGIN DOC", "// %title", "// a"]).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnclosedBlock);
+    assert_eq!(err.location.unwrap().line_number, 1);
+}
+
+#[test]
+fn test_empty_code() {
+    let err = parse_lines(&["// %=BEGIN DOC", "// %title", "// a", "// %=END DOC"]).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::EmptyCode);
+    // points the DOC block which has no code
+    assert_eq!(err.location.unwrap().line_number, 1);
+
+    let err = parse_lines(&["int a;"]).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::EmptyCode);
+    assert_eq!(err.location, None);
+}
+
+#[test]
+fn test_missing_title() {
+    let err = parse_lines(&[
+        "// %=BEGIN DOC",
+        "// %overview",
+        "// a",
+        "// %=END DOC",
+        "// %=BEGIN CODE",
+        "int a;",
+        "// %=END CODE",
+    ])
+    .unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::MissingTitle);
+    assert_eq!(err.location.unwrap().line_number, 1);
+}
+
+#[test]
+fn test_unterminated_code_fence() {
+    let err = parse_lines(&[
+        "// %=BEGIN DOC",
+        "// %require",
+        "// ```",
+        "#include <vector>",
+        "// %=END DOC",
+    ])
+    .unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnterminatedCodeFence);
+    let location = err.location.unwrap();
+    assert_eq!(location.line_number, 3);
+    assert_eq!(location.column, 4);
+}
+
+#[test]
+fn test_duplicate_article_name() {
+    let err = parse_lines(&[
+        "// %=BEGIN DOC a",
+        "// %title",
+        "// a",
+        "// %=END DOC",
+        "// %=BEGIN CODE",
+        "int a;",
+        "// %=END CODE",
+        "// %=BEGIN DOC a",
+        "// %title",
+        "// a",
+        "// %=END DOC",
+        "// %=BEGIN CODE",
+        "int b;",
+        "// %=END CODE",
+    ])
+    .unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::DuplicateArticleName("a".to_string())
+    );
+    assert_eq!(err.location.unwrap().line_number, 8);
+}
+
+#[test]
+fn test_duplicate_code_block_name() {
+    let err = parse_lines(&[
+        "// %=BEGIN DOC",
+        "// %title",
+        "// a",
+        "// %=END DOC",
+        "// %=BEGIN CODE core",
+        "int a;",
+        "// %=END CODE",
+        "// %=BEGIN CODE core",
+        "int b;",
+        "// %=END CODE",
+    ])
+    .unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::DuplicateCodeBlockName("core".to_string())
+    );
+    assert_eq!(err.location.unwrap().line_number, 8);
+}
+
+#[test]
+fn test_invalid_status() {
+    let err = parse_lines(&[
+        "// %=BEGIN DOC",
+        "// %status",
+        "//  obsolete",
+        "// %=END DOC",
+    ])
+    .unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::InvalidStatus("obsolete".to_string())
+    );
+    let location = err.location.unwrap();
+    assert_eq!(location.line_number, 3);
+    assert_eq!(location.column, 5);
+}
+
+#[test]
+fn test_conflicting_status() {
+    let err = parse_lines(&[
+        "// %=BEGIN DOC",
+        "// %title",
+        "// a",
+        "// %status",
+        "// stable",
+        "// %deprecated",
+        "// src/cpp/b.hpp",
+        "// %=END DOC",
+        "// %=BEGIN CODE",
+        "int a;",
+        "// %=END CODE",
+    ])
+    .unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::ConflictingStatus);
+    assert_eq!(err.location.unwrap().line_number, 5);
+}
@@ -0,0 +1,206 @@
+use super::{diff_lines, format_document_from_str, ParseErrorKind};
+
+#[test]
+fn test_canonical_order_and_blank_lines() {
+    let text = "\
+#pragma once
+//%=BEGIN DOC
+//
+// %usage
+// int add(int a, int b)
+//
+//
+// ; a + b
+// %title
+// add
+//
+//
+// %require
+//```
+#include <vector>
+//   ```
+// %=END DOC
+// %=BEGIN CODE
+int add(int a, int b) { return a + b; }
+// %=END CODE
+";
+    let expected = "\
+#pragma once
+// %=BEGIN DOC
+// %title
+// add
+//
+// %usage
+// int add(int a, int b)
+//
+// ; a + b
+//
+// %require
+// ```
+#include <vector>
+// ```
+// %=END DOC
+// %=BEGIN CODE
+int add(int a, int b) { return a + b; }
+// %=END CODE
+";
+    assert_eq!(format_document_from_str(text, "cpp").unwrap(), expected);
+    // formatting is idempotent
+    assert_eq!(format_document_from_str(expected, "cpp").unwrap(), expected);
+}
+
+#[test]
+fn test_localized_sections() {
+    let text = "\
+// %=BEGIN DOC
+// %overview.en
+// en
+// %title.en
+// Title
+// %overview
+// ja
+// %title
+// タイトル
+// %=END DOC
+";
+    let expected = "\
+// %=BEGIN DOC
+// %title
+// タイトル
+//
+// %title.en
+// Title
+//
+// %overview
+// ja
+//
+// %overview.en
+// en
+// %=END DOC
+";
+    assert_eq!(format_document_from_str(text, "cpp").unwrap(), expected);
+}
+
+#[test]
+fn test_several_articles() {
+    let text = "\
+# %=BEGIN DOC min
+# %overview
+# minimum
+# %title
+# Min
+# %=END DOC
+def min(a, b) = a < b ? a : b
+  # %=BEGIN DOC max
+  # %title
+  # Max
+  # %=END DOC
+def max(a, b) = a < b ? b : a";
+    let expected = "\
+# %=BEGIN DOC min
+# %title
+# Min
+#
+# %overview
+# minimum
+# %=END DOC
+def min(a, b) = a < b ? a : b
+  # %=BEGIN DOC max
+  # %title
+  # Max
+  # %=END DOC
+def max(a, b) = a < b ? b : a";
+    assert_eq!(format_document_from_str(text, "ruby").unwrap(), expected);
+}
+
+#[test]
+fn test_line_endings_are_kept() {
+    let text = "\u{feff}// %=BEGIN DOC\r\n// %title\r\n//\r\n// a\r\n// %=END DOC\r\nint a;\r\n";
+    assert_eq!(
+        format_document_from_str(text, "cpp").unwrap(),
+        "\u{feff}// %=BEGIN DOC\r\n// %title\r\n// a\r\n// %=END DOC\r\nint a;\r\n"
+    );
+}
+
+#[test]
+fn test_unformattable() {
+    let err =
+        format_document_from_str("/* %=BEGIN DOC\n%title\na\n%=END DOC */\n", "cpp").unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Unformattable(_)));
+    assert_eq!(err.location.unwrap().line_number, 1);
+
+    let err = format_document_from_str(
+        "// %=BEGIN DOC\n// %title\n// a\nint a;\n// %=END DOC\n",
+        "cpp",
+    )
+    .unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Unformattable(_)));
+    assert_eq!(err.location.unwrap().line_number, 4);
+}
+
+#[test]
+fn test_duplicate_section() {
+    let err = format_document_from_str(
+        "// %=BEGIN DOC\n// %title\n// a\n// %overview\n// b\n// %title\n// c\n// %=END DOC\nint a;\n",
+        "cpp",
+    )
+    .unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Unformattable(_)));
+    let location = err.location.unwrap();
+    assert_eq!(location.line_number, 6);
+    assert_eq!(location.column, 4);
+
+    // directly repeated
+    let err = format_document_from_str(
+        "// %=BEGIN DOC\n// %title\n// a\n// %title\n// %=END DOC\nint a;\n",
+        "cpp",
+    )
+    .unwrap_err();
+    assert_eq!(err.location.unwrap().line_number, 4);
+
+    // different locales are different sections
+    assert!(format_document_from_str(
+        "// %=BEGIN DOC\n// %title\n// a\n// %title.en\n// b\n// %=END DOC\nint a;\n",
+        "cpp",
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_indent_after_marker_is_kept() {
+    let text = "\
+// %=BEGIN DOC
+// %title
+// tree
+//
+// %overview
+// - list
+//   - nested
+//
+//     indented code
+// %=END DOC
+// %=BEGIN CODE
+int a;
+// %=END CODE
+";
+    assert_eq!(format_document_from_str(text, "cpp").unwrap(), text);
+}
+
+#[test]
+fn test_diff_lines() {
+    assert_eq!(diff_lines("a.hpp", "a\nb\n", "a\nb\n"), "");
+    assert_eq!(
+        diff_lines("a.hpp", "a\nb\nc\nd\n", "a\nB\nC\nD\nd\n"),
+        "--- a.hpp\n+++ a.hpp\n@@ -2,2 +2,3 @@\n-b\n-c\n+B\n+C\n+D\n"
+    );
+    assert_eq!(
+        diff_lines("a.hpp", "a\nb\n", "a\nc\nb\n"),
+        "--- a.hpp\n+++ a.hpp\n@@ -1,0 +2,1 @@\n+c\n"
+    );
+}
+
+#[test]
+fn test_front_matter_is_kept() {
+    let text = "// %=FRONT MATTER\n// ---\n// words: [a,b]\n// title:   a\n// ---\nint a;\n";
+    assert_eq!(format_document_from_str(text, "cpp").unwrap(), text);
+}
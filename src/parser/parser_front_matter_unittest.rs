@@ -0,0 +1,137 @@
+use super::{parse_document_from_str, ParseErrorKind};
+use crate::Article;
+use crate::ParseError;
+
+fn parse(text: &str) -> Result<Vec<Article>, ParseError> {
+    parse_document_from_str(
+        text,
+        "/src/cpp/a.hpp".to_string(),
+        "cpp".to_string(),
+        vec![],
+        vec![],
+    )
+}
+
+#[test]
+fn test_several_articles() {
+    let articles = parse(
+        "\
+// %=FRONT MATTER
+// ---
+// name: min
+// title: Min
+// ---
+// %=BEGIN CODE
+int min(int a, int b) { return a < b ? a : b; }
+// %=END CODE
+// ---
+// name: max
+// title: Max
+// ---
+// %=BEGIN CODE
+int max(int a, int b) { return a < b ? b : a; }
+// %=END CODE
+",
+    )
+    .unwrap();
+    let ids: Vec<&str> = articles.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, vec!["/src/cpp/a.hpp#min", "/src/cpp/a.hpp#max"]);
+    assert_eq!(articles[1].title, "Max");
+    assert_eq!(articles[1].doc_line_range.begin, 9);
+    assert_eq!(articles[1].doc_line_range.end, 12);
+}
+
+#[test]
+fn test_separator_in_anchor_file() {
+    // `---` is a plain comment unless the file has `%=FRONT MATTER`
+    let articles = parse(
+        "\
+// %=BEGIN DOC
+// %title
+// a
+// ---
+// %=END DOC
+// ---
+// %=BEGIN CODE
+int a;
+// %=END CODE
+",
+    )
+    .unwrap();
+    assert_eq!(articles[0].title, "a\n---");
+}
+
+#[test]
+fn test_banner_before_anchor() {
+    // `---` is a banner of an anchor syntax file, even if `key: value` follows
+    let articles = parse(
+        "\
+// ---
+// Sparse table
+// ---
+// %=BEGIN DOC
+// %title
+// a
+// %=END DOC
+// %=BEGIN CODE
+int a;
+// %=END CODE
+",
+    )
+    .unwrap();
+    assert_eq!(articles[0].title, "a");
+    assert_eq!(articles[0].doc_line_range.begin, 4);
+
+    // a code line after `---`
+    let articles = parse(
+        "// ---\nint b;\n// %=BEGIN DOC\n// %title\n// b\n// %=END DOC\n// %=BEGIN CODE\nint c;\n// %=END CODE\n",
+    )
+    .unwrap();
+    assert_eq!(articles[0].title, "b");
+
+    let articles = parse(
+        "// ---\n// Author: foo\n// ---\n// %=BEGIN DOC\n// %title\n// c\n// %=END DOC\n// %=BEGIN CODE\nint c;\n// %=END CODE\n",
+    )
+    .unwrap();
+    assert_eq!(articles[0].title, "c");
+}
+
+#[test]
+fn test_invalid_front_matter() {
+    let err = parse("// %=FRONT MATTER\n// ---\n// title: a\n// this is not a key\n// ---\n")
+        .unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::InvalidFrontMatter("expected `key: value`".to_string())
+    );
+    let location = err.location.unwrap();
+    assert_eq!(location.line_number, 4);
+    assert_eq!(location.column, 4);
+
+    let err = parse("// %=FRONT MATTER\n// ---\n//   b\n// ---\n").unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::InvalidFrontMatter("value without key".to_string())
+    );
+
+    let err = parse("// %=FRONT MATTER\n// ---\n// complexity:\n//   - build: O(N\n// ---\n")
+        .unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::InvalidComplexity(_)));
+    assert_eq!(err.location.unwrap().column, 15);
+
+    let err = parse("// %=FRONT MATTER\n// ---\n// title: a\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnclosedBlock);
+}
+
+#[test]
+fn test_mixed_doc_syntax() {
+    let err =
+        parse("// %=FRONT MATTER\n// ---\n// title: a\n// ---\n// %=BEGIN DOC\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::MixedDocSyntax);
+    assert_eq!(err.location.unwrap().line_number, 5);
+
+    let err =
+        parse("// %=BEGIN DOC\n// %title\n// a\n// %=END DOC\n// %=FRONT MATTER\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::MixedDocSyntax);
+    assert_eq!(err.location.unwrap().line_number, 5);
+}
@@ -0,0 +1,102 @@
+use super::preprocessor::{
+    logical_lines, split_directive, ConditionalTracker, LineState, LogicalLine,
+};
+
+fn texts(lines: &[LogicalLine]) -> Vec<(usize, &str)> {
+    lines
+        .iter()
+        .map(|line| (line.line_number, line.text.as_str()))
+        .collect()
+}
+
+// States of the lines which are not directives.
+fn line_states(text: &str) -> Vec<(String, LineState)> {
+    let mut tracker = ConditionalTracker::new();
+    let mut states = Vec::new();
+    for line in logical_lines(text) {
+        match split_directive(&line.text) {
+            Some((name, args)) => tracker.directive(name, args),
+            None => states.push((line.text.trim().to_string(), tracker.state())),
+        }
+    }
+    states
+}
+
+#[test]
+fn test_logical_lines() {
+    let lines = logical_lines(
+        "#define A \\\n  1\n#include \"a.hpp\" // comment\n/* #include \"b.hpp\"\n#include \"c.hpp\" */ int x;\nconst char *s = \"//\"; /* c */ int y;\n",
+    );
+    assert_eq!(
+        texts(&lines),
+        vec![
+            (1, "#define A   1"),
+            (3, "#include \"a.hpp\" "),
+            (4, ""),
+            (5, "  int x;"),
+            (6, "const char *s = \"//\";   int y;"),
+        ]
+    );
+}
+
+#[test]
+fn test_split_directive() {
+    assert_eq!(
+        split_directive("  #  include <vector>"),
+        Some(("include", "<vector>"))
+    );
+    assert_eq!(split_directive("#endif"), Some(("endif", "")));
+    assert_eq!(split_directive("int x; // #include"), None);
+}
+
+#[test]
+fn test_if_0() {
+    let states = line_states(
+        "a\n#if 0\nb\n#if X\nc\n#else\nd\n#endif\n#else\ne\n#endif\n#if 1\nf\n#elif X\ng\n#else\nh\n#endif\n",
+    );
+    assert_eq!(
+        states,
+        vec![
+            ("a".to_string(), LineState::Active),
+            ("b".to_string(), LineState::Inactive),
+            ("c".to_string(), LineState::Inactive),
+            ("d".to_string(), LineState::Inactive),
+            ("e".to_string(), LineState::Active),
+            ("f".to_string(), LineState::Active),
+            ("g".to_string(), LineState::Inactive),
+            ("h".to_string(), LineState::Inactive),
+        ]
+    );
+}
+
+#[test]
+fn test_macro_conditions() {
+    let states = line_states(
+        "#ifdef X\na\n#elif 1\nb\n#else\nc\n#endif\n#if defined(Y) && Z\nd\n#if 0\ne\n#endif\n#endif\n",
+    );
+    assert_eq!(
+        states,
+        vec![
+            ("a".to_string(), LineState::Conditional),
+            ("b".to_string(), LineState::Conditional),
+            ("c".to_string(), LineState::Inactive),
+            ("d".to_string(), LineState::Conditional),
+            ("e".to_string(), LineState::Inactive),
+        ]
+    );
+}
+
+#[test]
+fn test_include_guard() {
+    let states = line_states(
+        "#ifndef A_HPP\n#define A_HPP\na\n#ifndef NO_B\nb\n#endif\n#endif\n#ifndef C\n#define D\nc\n#endif\n",
+    );
+    assert_eq!(
+        states,
+        vec![
+            ("a".to_string(), LineState::Active),
+            ("b".to_string(), LineState::Conditional),
+            ("c".to_string(), LineState::Conditional),
+        ]
+    );
+}
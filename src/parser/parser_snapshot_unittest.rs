@@ -80,8 +80,24 @@ fn test_parse_file(path: &str) {
         serde_json::from_reader(File::open(path_expected).expect("Failed to open file"))
             .expect("Failed to parse JSON");
 
-    let parsed_article = crate::parser::parse_document_from_file(
+    let parsed_articles = crate::parser::parse_document_from_file(
         File::open(path).expect("Failed to open file"),
+        additional_info.path.clone(),
+        additional_info.lang.clone(),
+        additional_info.commits.clone(),
+        additional_info.tested_by.clone(),
+    )
+    .expect("Failed to parse article");
+
+    let articles_json =
+        serde_json::to_value(&parsed_articles).expect("Failed to serialize articles");
+
+    assert_eq!(articles_json, expected_json);
+
+    // parsing from str gives the same result
+    let text = std::fs::read_to_string(path).expect("Failed to read file");
+    let parsed_articles_from_str = crate::parser::parse_document_from_str(
+        &text,
         additional_info.path,
         additional_info.lang,
         additional_info.commits,
@@ -89,12 +105,61 @@ fn test_parse_file(path: &str) {
     )
     .expect("Failed to parse article");
 
-    let article_json = serde_json::to_value(&parsed_article).expect("Failed to serialize article");
+    let articles_json =
+        serde_json::to_value(&parsed_articles_from_str).expect("Failed to serialize articles");
 
-    assert_eq!(article_json, expected_json);
+    assert_eq!(articles_json, expected_json);
 }
 
 #[test]
 fn test() {
     test_parse_file("src/parser/unittest_resource/rmq_sparsetable.hpp");
+    test_parse_file("src/parser/unittest_resource/extra_sections.hpp");
+    test_parse_file("src/parser/unittest_resource/ruby_block_comment.rb");
+    test_parse_file("src/parser/unittest_resource/minmax_sparsetable.hpp");
+    test_parse_file("src/parser/unittest_resource/named_code_blocks.hpp");
+    test_parse_file("src/parser/unittest_resource/complexity_sections.hpp");
+    test_parse_file("src/parser/unittest_resource/example_section.hpp");
+    test_parse_file("src/parser/unittest_resource/localized_sections.hpp");
+    test_parse_file("src/parser/unittest_resource/front_matter.hpp");
+    test_parse_file("src/parser/unittest_resource/deprecated_section.hpp");
+}
+
+#[test]
+fn test_default_locale() {
+    let text = std::fs::read_to_string("src/parser/unittest_resource/localized_sections.hpp")
+        .expect("Failed to read file");
+    let articles = crate::parser::parse_document_from_str_with_locale(
+        &text,
+        "/src/cpp/localized_sections.hpp".to_string(),
+        "cpp".to_string(),
+        vec![],
+        vec![],
+        "en",
+    )
+    .expect("Failed to parse article");
+    let article = &articles[0];
+    assert_eq!(article.title, "Greatest common divisor");
+    assert_eq!(article.usage_entries[0].description, "gcd of a and b");
+    // sections without suffix stay in the source locale
+    assert_eq!(
+        article.localized_title.keys().collect::<Vec<_>>(),
+        vec!["en", "ja"]
+    );
+    assert_eq!(article.localized_title["ja"], "最大公約数");
+    assert_eq!(
+        article.localized_usage["ja"],
+        "int gcd(int a, int b)\n; a と b の最大公約数"
+    );
+    // falls back to the source locale if the default locale is missing
+    let articles = crate::parser::parse_document_from_str_with_locale(
+        "// %=BEGIN DOC\n// %title\n// 最大公約数\n// %=END DOC\n// %=BEGIN CODE\nint x;\n// %=END CODE\n",
+        "/src/cpp/a.hpp".to_string(),
+        "cpp".to_string(),
+        vec![],
+        vec![],
+        "en",
+    )
+    .expect("Failed to parse article");
+    assert_eq!(articles[0].title, "最大公約数");
 }
@@ -0,0 +1,76 @@
+use super::{decode_source, encode_source, parse_document_from_str, ParseErrorKind};
+
+#[test]
+fn test_bom_and_line_endings() {
+    let text = decode_source(b"\xef\xbb\xbfa\r\nb\rc\n", None).unwrap();
+    assert_eq!(text, "a\nb\nc\n");
+
+    // UTF-16LE with BOM
+    let text = decode_source(b"\xff\xfea\x00\r\x00\n\x00b\x00", None).unwrap();
+    assert_eq!(text, "a\nb");
+}
+
+#[test]
+fn test_invalid_utf8() {
+    let err = decode_source(b"// a\n// b\x82\xe6c\n", None).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::InvalidEncoding("UTF-8".to_string())
+    );
+    let location = err.location.unwrap();
+    assert_eq!(location.line_number, 2);
+    assert_eq!(location.column, 5);
+}
+
+#[test]
+fn test_fallback_encoding() {
+    // "区間" in Shift_JIS
+    let text = decode_source(b"// \x8b\xe6\x8a\xd4\r\n", Some("shift_jis")).unwrap();
+    assert_eq!(text, "// 区間\n");
+
+    // UTF-8 is preferred even if fallback is given
+    let text = decode_source("// 区間".as_bytes(), Some("shift_jis")).unwrap();
+    assert_eq!(text, "// 区間");
+
+    let err = decode_source(b"\x82", Some("unknown-encoding")).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::UnknownEncoding("unknown-encoding".to_string())
+    );
+}
+
+#[test]
+fn test_encode_source() {
+    // encoding, BOM and CRLF of the original are kept
+    let original = b"// \x8b\xe6\x8a\xd4\r\n";
+    let text = decode_source(original, Some("shift_jis")).unwrap();
+    assert_eq!(
+        encode_source(&text, original, Some("shift_jis")).unwrap(),
+        original
+    );
+    let original = b"\xef\xbb\xbfa\r\nb\r\n";
+    assert_eq!(encode_source("a\nb\n", original, None).unwrap(), original);
+    assert_eq!(encode_source("a\n", b"b\n", None).unwrap(), b"a\n");
+
+    // UTF-16 is not written
+    let err = encode_source("a", b"\xff\xfeb\x00", None).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::InvalidEncoding("UTF-16LE".to_string())
+    );
+}
+
+#[test]
+fn test_crlf_document() {
+    let text = "\u{feff}// %=BEGIN DOC\r\n// %title\r\n// a\r\n// %=END DOC\r\n// %=BEGIN CODE\r\nint a;\r\n// %=END CODE\r\n";
+    let articles = parse_document_from_str(
+        text,
+        "/src/cpp/a.hpp".to_string(),
+        "cpp".to_string(),
+        vec![],
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(articles[0].title, "a");
+    assert_eq!(articles[0].code, "int a;");
+}
@@ -0,0 +1,96 @@
+use super::parse_verified_url;
+use crate::codelib::Judge;
+
+fn classify(url: &str) -> (Option<Judge>, Option<String>, Option<String>) {
+    let entry = parse_verified_url(url);
+    assert_eq!(entry.url, url);
+    (entry.judge, entry.problem_id, entry.submission_id)
+}
+
+fn some(s: &str) -> Option<String> {
+    Some(s.to_string())
+}
+
+#[test]
+fn test_yukicoder() {
+    assert_eq!(
+        classify("https://yukicoder.me/problems/no/789"),
+        (Some(Judge::Yukicoder), some("789"), None)
+    );
+    assert_eq!(
+        classify("http://yukicoder.me/submissions/172470"),
+        (Some(Judge::Yukicoder), None, some("172470"))
+    );
+}
+
+#[test]
+fn test_atcoder() {
+    assert_eq!(
+        classify("https://atcoder.jp/contests/abc120/tasks/abc120_d"),
+        (Some(Judge::AtCoder), some("abc120_d"), None)
+    );
+    assert_eq!(
+        classify("https://atcoder.jp/contests/abc120/submissions/4445566"),
+        (Some(Judge::AtCoder), None, some("4445566"))
+    );
+    assert_eq!(
+        classify("https://abc120.contest.atcoder.jp/tasks/abc120_d"),
+        (Some(Judge::AtCoder), some("abc120_d"), None)
+    );
+}
+
+#[test]
+fn test_aoj() {
+    assert_eq!(
+        classify("https://onlinejudge.u-aizu.ac.jp/problems/ALDS1_1_A"),
+        (Some(Judge::Aoj), some("ALDS1_1_A"), None)
+    );
+    assert_eq!(
+        classify("https://onlinejudge.u-aizu.ac.jp/courses/library/5/GRL/1/GRL_1_A"),
+        (Some(Judge::Aoj), some("GRL_1_A"), None)
+    );
+    assert_eq!(
+        classify("http://judge.u-aizu.ac.jp/onlinejudge/description.jsp?id=DSL_2_A&lang=jp"),
+        (Some(Judge::Aoj), some("DSL_2_A"), None)
+    );
+    assert_eq!(
+        classify("https://onlinejudge.u-aizu.ac.jp/status/users/buyoh/submissions/1/DSL_2_A/judge/3868047/C++14"),
+        (Some(Judge::Aoj), some("DSL_2_A"), some("3868047"))
+    );
+}
+
+#[test]
+fn test_codeforces() {
+    assert_eq!(
+        classify("https://codeforces.com/contest/1000/problem/A"),
+        (Some(Judge::Codeforces), some("1000A"), None)
+    );
+    assert_eq!(
+        classify("https://codeforces.com/problemset/problem/1000/F1"),
+        (Some(Judge::Codeforces), some("1000F1"), None)
+    );
+    assert_eq!(
+        classify("https://codeforces.com/contest/1000/submission/39741234"),
+        (Some(Judge::Codeforces), None, some("39741234"))
+    );
+}
+
+#[test]
+fn test_library_checker() {
+    assert_eq!(
+        classify("https://judge.yosupo.jp/problem/staticrmq"),
+        (Some(Judge::LibraryChecker), some("staticrmq"), None)
+    );
+    assert_eq!(
+        classify("https://judge.yosupo.jp/submission/12345"),
+        (Some(Judge::LibraryChecker), None, some("12345"))
+    );
+}
+
+#[test]
+fn test_unknown() {
+    assert_eq!(
+        classify("http://tookunn.hatenablog.com/entry/2016/07/13/211148"),
+        (None, None, None)
+    );
+}
@@ -0,0 +1,222 @@
+// A lightweight C++ preprocessor for CodeInfo. It is not a real one: only
+// `#if 0` and `#if 1` are evaluated, and other conditions (`#ifdef FOO`,
+// `#if defined(FOO)`, ...) may be either way. Include guards
+// (`#ifndef X` followed by `#define X`) are not conditions.
+
+// A line after splicing `\` continuations and removing comments.
+pub struct LogicalLine {
+    pub line_number: usize, // 1-based, of the first physical line
+    pub text: String,
+}
+
+// Joins lines continued by `\`.
+fn splice_lines(text: &str) -> Vec<LogicalLine> {
+    let mut lines = Vec::new();
+    let mut pending: Option<LogicalLine> = None;
+    for (i, line) in text.lines().enumerate() {
+        let logical = pending.get_or_insert_with(|| LogicalLine {
+            line_number: i + 1,
+            text: String::new(),
+        });
+        match line.strip_suffix('\\') {
+            Some(continued) => logical.text.push_str(continued),
+            None => {
+                logical.text.push_str(line);
+                lines.extend(pending.take());
+            }
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+// Returns the position just after the closing quote, skipping escaped chars.
+fn find_quote_end(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            return Some(i + c.len_utf8());
+        }
+    }
+    None
+}
+
+// Replaces comments with a space as the preprocessor does. String and char
+// literals are kept. `in_block_comment` is carried across lines.
+fn strip_comments(line: &str, in_block_comment: &mut bool) -> String {
+    let mut stripped = String::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        if *in_block_comment {
+            match rest.find("*/") {
+                Some(pos) => {
+                    *in_block_comment = false;
+                    stripped.push(' ');
+                    rest = &rest[pos + 2..];
+                }
+                None => break,
+            }
+            continue;
+        }
+        if rest.starts_with("//") {
+            break;
+        }
+        if let Some(after) = rest.strip_prefix("/*") {
+            *in_block_comment = true;
+            rest = after;
+            continue;
+        }
+        let c = rest.chars().next().unwrap();
+        // 'a' or "..."; a quote after an identifier char is a digit separator
+        let prev_is_identifier = stripped
+            .chars()
+            .next_back()
+            .is_some_and(|prev| prev.is_alphanumeric() || prev == '_');
+        let len = match c {
+            '"' => find_quote_end(&rest[1..], '"').map(|pos| 1 + pos),
+            '\'' if !prev_is_identifier => find_quote_end(&rest[1..], '\'').map(|pos| 1 + pos),
+            _ => None,
+        }
+        .unwrap_or(c.len_utf8());
+        stripped.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    stripped
+}
+
+pub fn logical_lines(text: &str) -> Vec<LogicalLine> {
+    let mut in_block_comment = false;
+    splice_lines(text)
+        .into_iter()
+        .map(|line| LogicalLine {
+            line_number: line.line_number,
+            text: strip_comments(&line.text, &mut in_block_comment),
+        })
+        .collect()
+}
+
+// `# include <vector>` -> ("include", "<vector>")
+pub fn split_directive(text: &str) -> Option<(&str, &str)> {
+    let rest = text.trim_start().strip_prefix('#')?.trim_start();
+    let name_len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    Some((&rest[..name_len], rest[name_len..].trim()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Branch {
+    Taken,
+    Skipped,
+    Unknown, // depends on macros
+}
+
+struct Conditional {
+    branch: Branch,
+    decided: bool,         // a former branch is surely taken
+    unknown: bool,         // a former branch depends on macros
+    guard: Option<String>, // `#ifndef X` waiting for `#define X`
+}
+
+fn evaluate(condition: &str) -> Option<bool> {
+    match condition {
+        "0" | "false" => Some(false),
+        "1" | "true" => Some(true),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineState {
+    Inactive,    // e.g. in `#if 0`
+    Active,      // always compiled
+    Conditional, // compiled if some macros are (not) defined
+}
+
+// Tracks `#if` ... `#endif`. Unbalanced directives are tolerated.
+#[derive(Default)]
+pub struct ConditionalTracker {
+    stack: Vec<Conditional>,
+}
+
+impl ConditionalTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // State of lines after the directives given so far.
+    pub fn state(&self) -> LineState {
+        if self.stack.iter().any(|c| c.branch == Branch::Skipped) {
+            LineState::Inactive
+        } else if self.stack.iter().any(|c| c.branch == Branch::Unknown) {
+            LineState::Conditional
+        } else {
+            LineState::Active
+        }
+    }
+
+    // Must be called for every directive, including `#include`.
+    pub fn directive(&mut self, name: &str, args: &str) {
+        let guard = self.stack.last_mut().and_then(|top| top.guard.take());
+        let parent_inactive = self.state() == LineState::Inactive;
+        match name {
+            "if" | "ifdef" | "ifndef" => {
+                let condition = match name {
+                    "if" => evaluate(args),
+                    _ => None,
+                };
+                self.push(condition, parent_inactive);
+                if name == "ifndef" && !parent_inactive {
+                    self.stack.last_mut().unwrap().guard = Some(args.to_string());
+                }
+            }
+            "elif" => self.next_branch(evaluate(args)),
+            "else" => self.next_branch(Some(true)),
+            "endif" => {
+                self.stack.pop();
+            }
+            "define" => {
+                let defined = args.split_whitespace().next();
+                if guard.is_some() && guard.as_deref() == defined {
+                    let top = self.stack.last_mut().unwrap();
+                    top.branch = Branch::Taken;
+                    top.decided = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push(&mut self, condition: Option<bool>, parent_inactive: bool) {
+        let (branch, decided) = match condition {
+            // nothing in an inactive region is compiled
+            _ if parent_inactive => (Branch::Skipped, true),
+            Some(true) => (Branch::Taken, true),
+            Some(false) => (Branch::Skipped, false),
+            None => (Branch::Unknown, false),
+        };
+        self.stack.push(Conditional {
+            branch,
+            decided,
+            unknown: false,
+            guard: None,
+        });
+    }
+
+    fn next_branch(&mut self, condition: Option<bool>) {
+        let Some(top) = self.stack.last_mut() else {
+            return;
+        };
+        top.unknown |= top.branch == Branch::Unknown;
+        top.branch = match condition {
+            _ if top.decided => Branch::Skipped,
+            Some(false) => Branch::Skipped,
+            // taken unless a former branch depending on macros is
+            Some(true) if !top.unknown => Branch::Taken,
+            _ => Branch::Unknown,
+        };
+        top.decided |= condition == Some(true);
+    }
+}
@@ -0,0 +1,137 @@
+// Decoding of source files. Every line of the file must reach the parser;
+// undecodable input is reported instead of being skipped.
+
+use std::borrow::Cow;
+use std::io::Read;
+
+use encoding_rs::{Encoding, UTF_8};
+
+use super::{ParseError, ParseErrorKind, SourceLocation};
+
+// Strips BOM and normalises CRLF and CR to LF.
+pub fn normalize_source(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.contains('\r') {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+// Locates the first invalid byte for diagnostics.
+fn invalid_byte_location(bytes: &[u8], valid_up_to: usize) -> SourceLocation {
+    let line_begin = bytes[..valid_up_to]
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |pos| pos + 1);
+    let line_end = bytes[valid_up_to..]
+        .iter()
+        .position(|b| *b == b'\n')
+        .map_or(bytes.len(), |pos| valid_up_to + pos);
+    let line_number = bytes[..line_begin].iter().filter(|b| **b == b'\n').count() + 1;
+    // valid_up_to points to a char boundary, so the prefix is valid
+    let prefix = String::from_utf8_lossy(&bytes[line_begin..valid_up_to]);
+    let line = String::from_utf8_lossy(&bytes[line_begin..line_end]);
+    SourceLocation {
+        line_number,
+        column: prefix.chars().count() + 1,
+        line: line.trim_end_matches('\r').to_string(),
+        width: 1,
+    }
+}
+
+// Decodes bytes of a source file. BOM of UTF-8 and UTF-16 is honoured.
+// Input which is not UTF-8 is decoded by `fallback_encoding` (e.g. "shift_jis")
+// if given, otherwise it is an error.
+pub fn decode_source(bytes: &[u8], fallback_encoding: Option<&str>) -> Result<String, ParseError> {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        return decode_with(encoding, &bytes[bom_len..]);
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(normalize_source(text).into_owned()),
+        Err(err) => match fallback_encoding {
+            Some(label) => {
+                let encoding = Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+                    ParseError::new(ParseErrorKind::UnknownEncoding(label.to_string()), None)
+                })?;
+                decode_with(encoding, bytes)
+            }
+            None => Err(ParseError::new(
+                ParseErrorKind::InvalidEncoding(UTF_8.name().to_string()),
+                Some(invalid_byte_location(bytes, err.valid_up_to())),
+            )),
+        },
+    }
+}
+
+// Encodes `text` (LF, without BOM) like `original` was written: the same
+// encoding, BOM and CRLF line endings if `original` has them. Used to rewrite
+// files read by `decode_source`.
+pub fn encode_source(
+    text: &str,
+    original: &[u8],
+    fallback_encoding: Option<&str>,
+) -> Result<Vec<u8>, ParseError> {
+    let (encoding, bom) = match Encoding::for_bom(original) {
+        Some((encoding, bom_len)) => (encoding, &original[..bom_len]),
+        None if std::str::from_utf8(original).is_ok() => (UTF_8, &original[..0]),
+        None => {
+            let label = fallback_encoding.unwrap_or(UTF_8.name());
+            let encoding = Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+                ParseError::new(ParseErrorKind::UnknownEncoding(label.to_string()), None)
+            })?;
+            (encoding, &original[..0])
+        }
+    };
+    // UTF-16 can be decoded but not encoded by encoding_rs
+    if encoding.output_encoding() != encoding {
+        return Err(ParseError::new(
+            ParseErrorKind::InvalidEncoding(encoding.name().to_string()),
+            None,
+        ));
+    }
+    let text = if original.windows(2).any(|pair| pair == b"\r\n") {
+        Cow::Owned(text.replace('\n', "\r\n"))
+    } else {
+        Cow::Borrowed(text)
+    };
+    let (bytes, _, had_errors) = encoding.encode(&text);
+    if had_errors {
+        return Err(ParseError::new(
+            ParseErrorKind::InvalidEncoding(encoding.name().to_string()),
+            None,
+        ));
+    }
+    let mut encoded = bom.to_vec();
+    encoded.extend_from_slice(&bytes);
+    Ok(encoded)
+}
+
+fn decode_with(encoding: &'static Encoding, bytes: &[u8]) -> Result<String, ParseError> {
+    if encoding == UTF_8 {
+        return match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(normalize_source(text).into_owned()),
+            Err(err) => Err(ParseError::new(
+                ParseErrorKind::InvalidEncoding(UTF_8.name().to_string()),
+                Some(invalid_byte_location(bytes, err.valid_up_to())),
+            )),
+        };
+    }
+    match encoding.decode_without_bom_handling_and_without_replacement(bytes) {
+        Some(text) => Ok(normalize_source(&text).into_owned()),
+        None => Err(ParseError::new(
+            ParseErrorKind::InvalidEncoding(encoding.name().to_string()),
+            None,
+        )),
+    }
+}
+
+// Reads whole input as UTF-8 (or UTF-16 with BOM).
+pub fn read_source<R: Read>(mut reader: R) -> Result<String, ParseError> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|err| ParseError::new(ParseErrorKind::Io(err.to_string()), None))?;
+    decode_source(&bytes, None)
+}
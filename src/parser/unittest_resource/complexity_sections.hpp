@@ -0,0 +1,29 @@
+// %=BEGIN DOC
+// %title
+// Fenwick Tree
+//
+// %overview
+// 一点加算と区間和を計算する．
+//
+// %complexity
+// FenwickTree::FenwickTree(int n): O(N), O(N)
+// FenwickTree::add: O(logN)
+// FenwickTree::sum: O( log n )
+// lower_bound: O(log(N)^2)
+// %=END DOC
+// %=BEGIN CODE
+template <typename T>
+struct FenwickTree {
+  int n;
+  vector<T> data;
+  FenwickTree(int n) : n(n), data(n + 1) {}
+  void add(int i, T x) {
+    for (++i; i <= n; i += i & -i) data[i] += x;
+  }
+  T sum(int i) const {
+    T s = 0;
+    for (; i > 0; i -= i & -i) s += data[i];
+    return s;
+  }
+};
+// %=END CODE
@@ -0,0 +1,17 @@
+// %=BEGIN DOC
+// %title
+// 区間和 (古い実装)
+//
+// %status
+// deprecated
+//
+// %deprecated
+// src/cpp/container/fenwick_tree.hpp
+// %=END DOC
+// %=BEGIN CODE
+int sum(const int* a, int n) {
+  int s = 0;
+  for (int i = 0; i < n; ++i) s += a[i];
+  return s;
+}
+// %=END CODE
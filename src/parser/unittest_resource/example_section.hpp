@@ -0,0 +1,20 @@
+// %=BEGIN DOC
+// %title
+// gcd
+//
+// %require
+// ```
+#include <cassert>
+// ```
+//
+// %example
+// ```
+int main() {
+  assert(gcd(12, 18) == 6);
+  return 0;
+}
+// ```
+// %=END DOC
+// %=BEGIN CODE
+int gcd(int a, int b) { return b == 0 ? a : gcd(b, a % b); }
+// %=END CODE
@@ -0,0 +1,21 @@
+// %=BEGIN DOC
+// %title
+// Extra sections
+//
+// %overview
+// sections which are unknown for the parser are kept in `extra`.
+//
+// %usage
+// int add(int a, int b)
+// ; a + b
+//
+// %license
+// CC0
+//
+// %note
+// first line
+// second line
+// %=END DOC
+// %=BEGIN CODE
+int add(int a, int b) { return a + b; }
+// %=END CODE
@@ -0,0 +1,25 @@
+// %=FRONT MATTER
+// ---
+// name: min
+// title: SparseTable(区間最小)
+// title.en: Sparse table (range minimum)
+// overview: |
+//   構築済みの配列に対して，区間の最小値を計算する．
+//
+//   0-indexed で [begin, end)．
+// complexity:
+//   - build: O(NlogN), O(NlogN)
+//   - query: O(1)
+// verified:
+//   - https://judge.yosupo.jp/problem/staticrmq
+// words: [rmq, "doubling"]
+// license: CC0
+// ---
+#pragma once
+#include <vector>
+// %=BEGIN CODE
+template <typename T>
+struct SparseTableMin {
+  std::vector<std::vector<T>> table;
+};
+// %=END CODE
@@ -0,0 +1,19 @@
+// %=BEGIN DOC
+// %title
+// 最大公約数
+// %title.en
+// Greatest common divisor
+//
+// %overview.en
+// Computes gcd by Euclidean algorithm.
+//
+// %usage
+// int gcd(int a, int b)
+// ; a と b の最大公約数
+// %usage.en
+// int gcd(int a, int b)
+// ; gcd of a and b
+// %=END DOC
+// %=BEGIN CODE
+int gcd(int a, int b) { return b == 0 ? a : gcd(b, a % b); }
+// %=END CODE
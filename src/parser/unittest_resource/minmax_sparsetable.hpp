@@ -0,0 +1,29 @@
+#pragma once
+#include <vector>
+// %=BEGIN DOC min
+// %title
+// SparseTable(区間最小)
+//
+// %overview
+// 区間の最小値を計算する．
+// %=END DOC
+// %=BEGIN CODE
+template <typename T>
+struct MinSparseTable {
+  std::vector<std::vector<T>> dp;
+};
+// %=END CODE
+
+// %=BEGIN DOC max
+// %title
+// SparseTable(区間最大)
+//
+// %overview
+// 区間の最大値を計算する．
+// %=END DOC
+// %=BEGIN CODE
+template <typename T>
+struct MaxSparseTable {
+  std::vector<std::vector<T>> dp;
+};
+// %=END CODE
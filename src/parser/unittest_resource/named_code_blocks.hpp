@@ -0,0 +1,27 @@
+// %=BEGIN DOC
+// %title
+// Fenwick Tree
+// %=END DOC
+// %=BEGIN CODE core
+struct FenwickTree {
+  std::vector<long long> data;
+  void add(int i, long long x) {
+    for (++i; i < (int)data.size(); i += i & -i) data[i] += x;
+  }
+};
+// %=END CODE
+
+// %=BEGIN CODE helper
+long long sum(const FenwickTree& ft, int i) {
+  long long s = 0;
+  for (; i > 0; i -= i & -i) s += ft.data[i];
+  return s;
+}
+// %=END CODE
+
+// %=BEGIN CODE example
+int main() {
+  FenwickTree ft{std::vector<long long>(11)};
+  ft.add(3, 1);
+}
+// %=END CODE
@@ -0,0 +1,19 @@
+=begin
+%=BEGIN DOC
+%title
+Union-Find
+
+%overview
+素集合データ構造．
+=end
+# %verified
+# https://atcoder.jp/contests/abc120/submissions/4445566
+# %=END DOC
+# %=BEGIN CODE
+class UnionFind
+  # path compression
+  def initialize(n)
+    @parent = Array.new(n, -1)
+  end
+end
+# %=END CODE
@@ -0,0 +1,115 @@
+// Classification of %verified URLs by online judge. Only the URL shape is
+// looked at; nothing is fetched.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::codelib::{Judge, VerifiedEntry};
+
+// Named groups:
+// - `problem`: problem id
+// - `contest` and `index`: problem id is `contest` + `index` (e.g. 1000A)
+// - `submission`: submission id
+const JUDGE_URL_PATTERNS: &[(Judge, &str)] = &[
+    (
+        Judge::Yukicoder,
+        r"^https?://yukicoder\.me/problems/no/(?P<problem>\d+)",
+    ),
+    (
+        Judge::Yukicoder,
+        r"^https?://yukicoder\.me/submissions/(?P<submission>\d+)",
+    ),
+    (
+        Judge::AtCoder,
+        r"^https?://atcoder\.jp/contests/[^/]+/tasks/(?P<problem>[^/?#]+)",
+    ),
+    (
+        Judge::AtCoder,
+        r"^https?://atcoder\.jp/contests/[^/]+/submissions/(?P<submission>\d+)",
+    ),
+    (
+        Judge::AtCoder,
+        r"^https?://[^./]+\.contest\.atcoder\.jp/tasks/(?P<problem>[^/?#]+)",
+    ),
+    (
+        Judge::AtCoder,
+        r"^https?://[^./]+\.contest\.atcoder\.jp/submissions/(?P<submission>\d+)",
+    ),
+    (
+        Judge::Aoj,
+        r"^https?://onlinejudge\.u-aizu\.ac\.jp/(?:status/users/[^/]+/submissions/\d+|solutions/problem)/(?P<problem>[^/?#]+)/(?:judge|review)/(?P<submission>\d+)",
+    ),
+    (
+        Judge::Aoj,
+        r"^https?://onlinejudge\.u-aizu\.ac\.jp/problems/(?P<problem>[^/?#]+)",
+    ),
+    (
+        Judge::Aoj,
+        r"^https?://onlinejudge\.u-aizu\.ac\.jp/courses/(?:[^/?#]+/)*(?P<problem>[^/?#]+)/?$",
+    ),
+    (
+        Judge::Aoj,
+        r"^https?://judge\.u-aizu\.ac\.jp/onlinejudge/description\.jsp\?id=(?P<problem>[^&#]+)",
+    ),
+    (
+        Judge::Aoj,
+        r"^https?://judge\.u-aizu\.ac\.jp/onlinejudge/review\.jsp\?rid=(?P<submission>\d+)",
+    ),
+    (
+        Judge::Codeforces,
+        r"^https?://(?:www\.)?codeforces\.com/(?:contest|gym)/(?P<contest>\d+)/problem/(?P<index>[A-Za-z0-9]+)",
+    ),
+    (
+        Judge::Codeforces,
+        r"^https?://(?:www\.)?codeforces\.com/problemset/problem/(?P<contest>\d+)/(?P<index>[A-Za-z0-9]+)",
+    ),
+    (
+        Judge::Codeforces,
+        r"^https?://(?:www\.)?codeforces\.com/(?:contest/\d+|gym/\d+|problemset)/submission/(?:\d+/)?(?P<submission>\d+)",
+    ),
+    (
+        Judge::LibraryChecker,
+        r"^https?://judge\.yosupo\.jp/problem/(?P<problem>[^/?#]+)",
+    ),
+    (
+        Judge::LibraryChecker,
+        r"^https?://judge\.yosupo\.jp/submission/(?P<submission>\d+)",
+    ),
+];
+
+fn judge_url_regexes() -> &'static Vec<(Judge, Regex)> {
+    static REGEXES: OnceLock<Vec<(Judge, Regex)>> = OnceLock::new();
+    REGEXES.get_or_init(|| {
+        JUDGE_URL_PATTERNS
+            .iter()
+            // unwrap is ok because the patterns are always valid
+            .map(|(judge, pattern)| (*judge, Regex::new(pattern).unwrap()))
+            .collect()
+    })
+}
+
+pub fn parse_verified_url(url: &str) -> VerifiedEntry {
+    for (judge, regex) in judge_url_regexes() {
+        if let Some(captures) = regex.captures(url) {
+            let group = |name: &str| captures.name(name).map(|m| m.as_str().to_string());
+            let problem_id = group("problem").or_else(|| {
+                group("contest")
+                    .zip(group("index"))
+                    .map(|(contest, index)| format!("{}{}", contest, index))
+            });
+            return VerifiedEntry {
+                url: url.to_string(),
+                judge: Some(*judge),
+                problem_id,
+                submission_id: group("submission"),
+            };
+        }
+    }
+    VerifiedEntry {
+        url: url.to_string(),
+        judge: None,
+        problem_id: None,
+        submission_id: None,
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::codelib::{Judge, ProblemIndexEntry, VerifiedEntry};
+use crate::Article;
+
+#[cfg(test)]
+mod unittest;
+
+#[derive(Default)]
+struct ProblemIndexBuilder {
+    urls: BTreeSet<String>,
+    articles: BTreeSet<String>,
+    test_files: BTreeSet<String>,
+}
+
+fn problem_key(entry: &VerifiedEntry) -> Option<(Judge, String)> {
+    Some((entry.judge?, entry.problem_id.clone()?))
+}
+
+// Maps each judge problem to articles verified against it and test files
+// referencing it. Entries without problem id (e.g. submission URLs) are ignored.
+pub fn build_problem_index(
+    articles: &[Article],
+    test_references: &[(String, Vec<VerifiedEntry>)],
+) -> Vec<ProblemIndexEntry> {
+    let mut builders: BTreeMap<(Judge, String), ProblemIndexBuilder> = BTreeMap::new();
+    for article in articles {
+        for entry in &article.verified_entries {
+            if let Some(key) = problem_key(entry) {
+                let builder = builders.entry(key).or_default();
+                builder.urls.insert(entry.url.clone());
+                builder.articles.insert(article.id.clone());
+            }
+        }
+    }
+    for (test_path, entries) in test_references {
+        for entry in entries {
+            if let Some(key) = problem_key(entry) {
+                let builder = builders.entry(key).or_default();
+                builder.urls.insert(entry.url.clone());
+                builder.test_files.insert(test_path.clone());
+            }
+        }
+    }
+    builders
+        .into_iter()
+        .map(|((judge, problem_id), builder)| ProblemIndexEntry {
+            judge,
+            problem_id,
+            urls: builder.urls.into_iter().collect(),
+            articles: builder.articles.into_iter().collect(),
+            test_files: builder.test_files.into_iter().collect(),
+        })
+        .collect()
+}
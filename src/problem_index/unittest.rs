@@ -0,0 +1,59 @@
+use crate::codelib::{Article, Judge};
+use crate::parser::parse_verified_url;
+
+#[cfg(test)]
+use super::build_problem_index;
+
+fn article(id: &str, verified: &[&str]) -> Article {
+    Article {
+        id: id.to_string(),
+        lang: "cpp".to_string(),
+        path: id.split('#').next().unwrap().to_string(),
+        verified: verified.iter().map(|s| s.to_string()).collect(),
+        verified_entries: verified.iter().map(|s| parse_verified_url(s)).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_build_problem_index() {
+    let articles = vec![
+        article(
+            "/src/cpp/a.hpp",
+            &[
+                "https://judge.yosupo.jp/problem/staticrmq",
+                "https://yukicoder.me/submissions/172470",
+            ],
+        ),
+        article(
+            "/src/cpp/b.hpp#min",
+            &["https://judge.yosupo.jp/problem/staticrmq"],
+        ),
+    ];
+    let test_references = vec![(
+        "/test/cpp/c.test.cpp".to_string(),
+        vec![
+            parse_verified_url("https://atcoder.jp/contests/abc120/tasks/abc120_d"),
+            parse_verified_url("https://judge.yosupo.jp/problem/staticrmq"),
+        ],
+    )];
+    let index = build_problem_index(&articles, &test_references);
+    assert_eq!(index.len(), 2);
+
+    assert_eq!(index[0].judge, Judge::AtCoder);
+    assert_eq!(index[0].problem_id, "abc120_d");
+    assert!(index[0].articles.is_empty());
+    assert_eq!(index[0].test_files, vec!["/test/cpp/c.test.cpp"]);
+
+    assert_eq!(index[1].judge, Judge::LibraryChecker);
+    assert_eq!(index[1].problem_id, "staticrmq");
+    assert_eq!(
+        index[1].urls,
+        vec!["https://judge.yosupo.jp/problem/staticrmq"]
+    );
+    assert_eq!(
+        index[1].articles,
+        vec!["/src/cpp/a.hpp", "/src/cpp/b.hpp#min"]
+    );
+    assert_eq!(index[1].test_files, vec!["/test/cpp/c.test.cpp"]);
+}
@@ -14,7 +14,7 @@ enum CodeIndex {
 
 struct RelationInternalSolver<'a> {
     collection: &'a Collection,
-    code_info_sets_vec: &'a Vec<CodeInfoSets>,
+    code_info_sets_vec: &'a [CodeInfoSets],
 
     // source_relations: Vec<SourceRelationSolving>,
     path_to_code_index: BTreeMap<String, CodeIndex>,
@@ -45,10 +45,12 @@ impl<'a> RelationInternalSolver<'a> {
         }
     }
 
+    // Follows `conditional_dependencies` too if `conditional` is true.
     fn solve_internal_tested_by_dfs(
         &self,
         code_index: &CodeIndex,
         visited: &mut BTreeSet<CodeIndex>,
+        conditional: bool,
     ) {
         if visited.contains(code_index) {
             return;
@@ -56,9 +58,18 @@ impl<'a> RelationInternalSolver<'a> {
         visited.insert(*code_index);
 
         let code_info = self.get_code_info(code_index);
-        for path_string in &code_info.filepath_dependencies {
+        let conditional_dependencies = if conditional {
+            &code_info.conditional_dependencies[..]
+        } else {
+            &[]
+        };
+        for path_string in code_info
+            .filepath_dependencies
+            .iter()
+            .chain(conditional_dependencies)
+        {
             if let Some(next_code_index) = self.path_to_code_index.get(path_string) {
-                self.solve_internal_tested_by_dfs(next_code_index, visited);
+                self.solve_internal_tested_by_dfs(next_code_index, visited, conditional);
             } else {
                 // TODO: print error if in debug mode
             }
@@ -77,20 +88,31 @@ impl<'a> RelationInternalSolver<'a> {
                 let mut source_relations = vec![
                     SourceRelation {
                         tested_by: Vec::new(),
+                        conditionally_tested_by: Vec::new(),
                     };
                     src_code_len
                 ];
                 for (i, _tci) in code_info_sets.test_code_infos.iter().enumerate() {
                     let code_index = CodeIndex::Test(lang_idx, i);
                     let mut visited = BTreeSet::new();
-                    self.solve_internal_tested_by_dfs(&code_index, &mut visited);
-
-                    for ci in visited.iter() {
+                    self.solve_internal_tested_by_dfs(&code_index, &mut visited, false);
+                    let mut conditionally_visited = BTreeSet::new();
+                    self.solve_internal_tested_by_dfs(
+                        &code_index,
+                        &mut conditionally_visited,
+                        true,
+                    );
+
+                    for ci in conditionally_visited.iter() {
                         // collect src code depended by the test code.
                         // src code depended by test code is
                         match ci {
                             CodeIndex::Src(lang_idx2, code_idx) if lang_idx == *lang_idx2 => {
-                                source_relations[*code_idx].tested_by.push(i);
+                                if visited.contains(ci) {
+                                    source_relations[*code_idx].tested_by.push(i);
+                                } else {
+                                    source_relations[*code_idx].conditionally_tested_by.push(i);
+                                }
                             }
                             _ => {}
                         }
@@ -105,7 +127,7 @@ impl<'a> RelationInternalSolver<'a> {
 
     fn solve(
         collection: &Collection,
-        code_info_sets_vec: &Vec<CodeInfoSets>,
+        code_info_sets_vec: &[CodeInfoSets],
     ) -> Result<Relations, String> {
         // validation
         // TODO: Modfy struct to be unecessary this validation
@@ -137,6 +159,8 @@ impl<'a> RelationInternalSolver<'a> {
 #[derive(Clone)]
 pub struct SourceRelation {
     pub tested_by: Vec<usize>, // test_code_idx
+    // reached only through conditional_dependencies
+    pub conditionally_tested_by: Vec<usize>,
 }
 
 pub struct Relations {
@@ -147,7 +171,7 @@ pub struct Relations {
 // TODO: Re-consider interface: especially src_code_infos and test_code_infos
 pub fn solve_relation(
     collection: &Collection,
-    code_info_sets_vec: &Vec<CodeInfoSets>,
+    code_info_sets_vec: &[CodeInfoSets],
 ) -> Result<Relations, String> {
     RelationInternalSolver::solve(collection, code_info_sets_vec)
 }
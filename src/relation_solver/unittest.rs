@@ -17,29 +17,39 @@ fn test() {
             ],
             test_paths: vec!["test/1.cpp".to_string(), "test/2.cpp".to_string()],
         }],
+        fallback_encoding: None,
+        default_locale: None,
+        include_roots: vec![],
+        library_prefix: None,
     };
     let code_info_sets = vec![CodeInfoSets {
         lang: "cpp".to_string(),
         src_code_infos: vec![
             CodeInfo {
                 filepath_dependencies: vec![],
+                ..Default::default()
             },
             CodeInfo {
                 filepath_dependencies: vec!["src/a/x.hpp".to_string()],
+                ..Default::default()
             },
             CodeInfo {
                 filepath_dependencies: vec!["src/a/x.hpp".to_string()],
+                ..Default::default()
             },
             CodeInfo {
                 filepath_dependencies: vec!["src/a/x.hpp".to_string(), "src/b/x.hpp".to_string()],
+                ..Default::default()
             },
         ],
         test_code_infos: vec![
             CodeInfo {
                 filepath_dependencies: vec!["src/a/x.hpp".to_string()],
+                ..Default::default()
             },
             CodeInfo {
                 filepath_dependencies: vec!["src/b/y.hpp".to_string()],
+                ..Default::default()
             },
         ],
     }];
@@ -53,3 +63,48 @@ fn test() {
     assert_eq!(source_relation[3].tested_by, vec![1]);
     assert_eq!(source_relation[0].tested_by, vec![0, 1]);
 }
+
+#[test]
+fn test_conditional_dependencies() {
+    let collection = Collection {
+        base_path: "test".to_string(),
+        source_sets: vec![SourceSets {
+            lang: "cpp".to_string(),
+            src_paths: vec!["src/x.hpp".to_string(), "src/y.hpp".to_string()],
+            test_paths: vec!["test/1.cpp".to_string(), "test/2.cpp".to_string()],
+        }],
+        fallback_encoding: None,
+        default_locale: None,
+        include_roots: vec![],
+        library_prefix: None,
+    };
+    let code_info_sets = vec![CodeInfoSets {
+        lang: "cpp".to_string(),
+        src_code_infos: vec![
+            CodeInfo::default(),
+            CodeInfo {
+                conditional_dependencies: vec!["src/x.hpp".to_string()],
+                ..Default::default()
+            },
+        ],
+        test_code_infos: vec![
+            CodeInfo {
+                filepath_dependencies: vec!["src/y.hpp".to_string()],
+                ..Default::default()
+            },
+            CodeInfo {
+                filepath_dependencies: vec!["src/x.hpp".to_string(), "src/y.hpp".to_string()],
+                ..Default::default()
+            },
+        ],
+    }];
+    let relations = solve_relation(&collection, &code_info_sets).unwrap();
+    let source_relation = &relations.source_relations[0];
+    assert_eq!(source_relation[0].tested_by, vec![1]);
+    assert_eq!(source_relation[0].conditionally_tested_by, vec![0]);
+    assert_eq!(source_relation[1].tested_by, vec![0, 1]);
+    assert_eq!(
+        source_relation[1].conditionally_tested_by,
+        vec![] as Vec<usize>
+    );
+}
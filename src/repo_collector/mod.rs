@@ -1,6 +1,10 @@
 use glob::glob;
+use std::process::Command;
 
-use crate::{Collection, SourceSets};
+use crate::{Collection, CollectionOptions, Commit, SourceSets};
+
+#[cfg(test)]
+mod unittest;
 
 fn collect_langs(base_path: &str) -> Result<Vec<String>, String> {
     let mut langs = Vec::new();
@@ -9,13 +13,11 @@ fn collect_langs(base_path: &str) -> Result<Vec<String>, String> {
     } else {
         return Err("Failed to read glob pattern; base_path may be wrong".to_string());
     };
-    for entry in g {
-        // if entry is directory
-        if let Ok(entry) = entry {
-            if entry.is_dir() {
-                let lang = entry.file_name().unwrap().to_str().unwrap().to_string();
-                langs.push(lang);
-            }
+    // if entry is directory
+    for entry in g.flatten() {
+        if entry.is_dir() {
+            let lang = entry.file_name().unwrap().to_str().unwrap().to_string();
+            langs.push(lang);
         }
     }
     Ok(langs)
@@ -24,7 +26,7 @@ fn collect_langs(base_path: &str) -> Result<Vec<String>, String> {
 // TODO: Remove panic
 fn collect_paths(
     base_path: &str,
-    langs: &Vec<String>,
+    langs: &[String],
     src_or_test: &str,
 ) -> Result<Vec<Vec<String>>, String> {
     let mut src_lang_paths: Vec<Vec<String>> = Vec::new();
@@ -35,14 +37,12 @@ fn collect_paths(
         } else {
             return Err("Failed to read glob pattern; base_path may be wrong".to_string());
         };
-        for entry in g {
-            if let Ok(entry) = entry {
-                // if entry is file
-                if entry.is_file() {
-                    let stripped_path = entry.strip_prefix(base_path).unwrap();
-                    src_paths.push(format!("/{}", stripped_path.to_str().unwrap()));
-                    // add '/' to the beginning
-                }
+        for entry in g.flatten() {
+            // if entry is file
+            if entry.is_file() {
+                let stripped_path = entry.strip_prefix(base_path).unwrap();
+                src_paths.push(format!("/{}", stripped_path.to_str().unwrap()));
+                // add '/' to the beginning
             }
         }
         src_lang_paths.push(src_paths);
@@ -50,37 +50,78 @@ fn collect_paths(
     Ok(src_lang_paths)
 }
 
-pub fn gather_collection(base_path: &str) -> Result<Collection, String> {
-    let langs = match collect_langs(base_path) {
-        Ok(langs) => langs,
-        Err(err) => return Err(err),
-    };
-    let src_paths = match collect_paths(base_path, &langs, "src") {
-        Ok(src_paths) => src_paths,
-        Err(err) => return Err(err),
-    };
-    let test_paths = match collect_paths(base_path, &langs, "test") {
-        Ok(test_paths) => test_paths,
-        Err(err) => return Err(err),
-    };
+pub fn gather_collection(
+    base_path: &str,
+    options: CollectionOptions,
+) -> Result<Collection, String> {
+    let langs = collect_langs(base_path)?;
+    let src_paths = collect_paths(base_path, &langs, "src")?;
+    let test_paths = collect_paths(base_path, &langs, "test")?;
 
     let collection = Collection {
         base_path: base_path.to_string(),
         source_sets: src_paths
             .into_iter()
-            .zip(test_paths.into_iter())
-            .zip(langs.into_iter())
+            .zip(test_paths)
+            .zip(langs)
             .map(|((src_paths, test_paths), lang)| SourceSets {
                 lang,
                 src_paths,
                 test_paths,
             })
             .collect(),
+        fallback_encoding: options.fallback_encoding,
+        default_locale: options.default_locale,
+        include_roots: options.include_roots,
+        library_prefix: options.library_prefix,
     };
     Ok(collection)
 }
 
-pub fn gather_commit_info(_filepath: &str) -> Result<Vec<crate::codelib::Commit>, String> {
-    // TODO: implement
-    Ok(Vec::new())
+// Separators which never appear in sha, date and subject of `git log`.
+const GIT_LOG_FIELD_SEPARATOR: char = '\x1f';
+const GIT_LOG_RECORD_SEPARATOR: char = '\x1e';
+
+fn parse_git_log_output(output: &str) -> Result<Vec<Commit>, String> {
+    let mut commits = Vec::new();
+    for record in output.split(GIT_LOG_RECORD_SEPARATOR) {
+        let record = record.trim_matches('\n');
+        if record.is_empty() {
+            continue;
+        }
+        let mut fields = record.splitn(3, GIT_LOG_FIELD_SEPARATOR);
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(sha), Some(date), Some(message)) => commits.push(Commit {
+                sha: sha.to_string(),
+                date: date.to_string(),
+                message: message.to_string(),
+            }),
+            _ => return Err(format!("Unexpected git log record: {}", record)),
+        }
+    }
+    Ok(commits)
+}
+
+// Collect the commits which modified `path` (newest first), following renames.
+// `path` is relative to `base_path`, e.g. "/src/cpp/a.hpp".
+pub fn gather_commit_info(base_path: &str, path: &str) -> Result<Vec<Commit>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(base_path)
+        .arg("log")
+        .arg("--follow")
+        .arg("--no-color")
+        .arg("--format=%H%x1f%ai%x1f%s%x1e")
+        .arg("--")
+        .arg(path.trim_start_matches('/'))
+        .output()
+        .map_err(|err| format!("Failed to execute git: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_git_log_output(&stdout)
 }
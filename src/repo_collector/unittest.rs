@@ -0,0 +1,42 @@
+#[cfg(test)]
+use super::{gather_commit_info, parse_git_log_output};
+#[cfg(test)]
+use crate::test_util::TempDir;
+
+#[test]
+fn test_parse_git_log_output() {
+    let output = "1703cfd5937491aed191816ef0f1d37908630f24\x1f2020-06-28 03:09:01 +0900\x1fAdd include guard and Modify document formats (#64)\x1e\n\
+        ece0b45f2a0f37c91fece075aebbcf5b39cfd902\x1f2020-06-24 00:23:28 +0900\x1frename cpp to hpp (#62)\x1e\n";
+    let commits = parse_git_log_output(output).unwrap();
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].sha, "1703cfd5937491aed191816ef0f1d37908630f24");
+    assert_eq!(commits[0].date, "2020-06-28 03:09:01 +0900");
+    assert_eq!(
+        commits[0].message,
+        "Add include guard and Modify document formats (#64)"
+    );
+    assert_eq!(commits[1].sha, "ece0b45f2a0f37c91fece075aebbcf5b39cfd902");
+    assert_eq!(commits[1].message, "rename cpp to hpp (#62)");
+}
+
+#[test]
+fn test_parse_git_log_output_empty() {
+    assert!(parse_git_log_output("").unwrap().is_empty());
+    assert!(parse_git_log_output("broken\x1e").is_err());
+}
+
+#[test]
+fn test_gather_commit_info_follows_rename() {
+    let repo = TempDir::with_repository("git_log", &[("src/cpp/a.cpp", "int a;\n")]);
+    repo.git(&["mv", "src/cpp/a.cpp", "src/cpp/a.hpp"]);
+    repo.git(&["commit", "-q", "-m", "rename cpp to hpp"]);
+
+    let commits = gather_commit_info(repo.path_str(), "/src/cpp/a.hpp").unwrap();
+    let messages: Vec<&str> = commits.iter().map(|c| c.message.as_str()).collect();
+    assert_eq!(messages, vec!["rename cpp to hpp", "Add files"]);
+    assert!(commits.iter().all(|c| c.sha.len() == 40));
+
+    assert!(gather_commit_info(repo.path_str(), "/src/cpp/none.hpp")
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,61 @@
+// Helpers shared by unit tests.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+// A directory under the system temp dir, removed on drop even if the test
+// panics. A directory left by an earlier run with the same pid is cleared.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("codelib_{}_{}", name, std::process::id()));
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    // A git repository with `files` committed, so that commits of them are found.
+    pub fn with_repository(name: &str, files: &[(&str, &str)]) -> Self {
+        let dir = Self::new(name);
+        dir.git(&["init", "-q"]);
+        dir.write_files(files);
+        dir.git(&["add", "-A"]);
+        dir.git(&["commit", "-q", "-m", "Add files"]);
+        dir
+    }
+
+    pub fn path_str(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+
+    // `files` are (path relative to the directory, text)
+    pub fn write_files(&self, files: &[(&str, &str)]) {
+        for (path, text) in files {
+            let path = self.path.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        }
+    }
+
+    pub fn git(&self, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&self.path)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{CodeInfo, CodeInfoSets, Collection, Judge, ParseErrorKind, SourceSets};
+
+#[cfg(test)]
+use super::{complete_articles, complete_problem_index, read_source_file};
+#[cfg(test)]
+use crate::test_util::TempDir;
+
+fn collection(base_path: &str, src_paths: &[&str], test_paths: &[&str]) -> Collection {
+    Collection {
+        base_path: base_path.to_string(),
+        source_sets: vec![SourceSets {
+            lang: "cpp".to_string(),
+            src_paths: src_paths.iter().map(|s| s.to_string()).collect(),
+            test_paths: test_paths.iter().map(|s| s.to_string()).collect(),
+        }],
+        fallback_encoding: None,
+        default_locale: None,
+        include_roots: vec![],
+        library_prefix: None,
+    }
+}
+
+#[test]
+fn test_complete_problem_index() {
+    let collection = collection(
+        "test",
+        &[],
+        &[
+            "/test/cpp/a.test.cpp",
+            "/test/cpp/b.test.cpp",
+            "/test/cpp/c.test.cpp",
+        ],
+    );
+    let code_info_sets = vec![CodeInfoSets {
+        lang: "cpp".to_string(),
+        src_code_infos: vec![],
+        test_code_infos: vec![
+            CodeInfo {
+                problem: Some("https://judge.yosupo.jp/problem/staticrmq".to_string()),
+                ..Default::default()
+            },
+            CodeInfo {
+                problem: Some("https://judge.yosupo.jp/problem/point_add_range_sum".to_string()),
+                ignore: true,
+                ..Default::default()
+            },
+            CodeInfo::default(),
+        ],
+    }];
+    let index = complete_problem_index(&collection, &code_info_sets, &[]);
+    assert_eq!(index.len(), 1);
+    assert_eq!(index[0].judge, Judge::LibraryChecker);
+    assert_eq!(index[0].problem_id, "staticrmq");
+    assert_eq!(index[0].test_files, vec!["/test/cpp/a.test.cpp"]);
+}
+
+#[test]
+fn test_complete_articles_verified_by_tests() {
+    let files = [
+        (
+            "src/cpp/a.hpp",
+            "// %=BEGIN DOC\n// %title\n// A\n// %verified\n// https://judge.yosupo.jp/problem/staticrmq\n// %=END DOC\n// %=BEGIN CODE\nint a;\n// %=END CODE\n",
+        ),
+        (
+            "test/cpp/a.test.cpp",
+            "#define PROBLEM \"https://judge.yosupo.jp/problem/unionfind\"\n#include \"../../src/cpp/a.hpp\"\n",
+        ),
+        (
+            "test/cpp/b.test.cpp",
+            "#define PROBLEM \"https://judge.yosupo.jp/problem/aplusb\"\n#define IGNORE\n#include \"../../src/cpp/a.hpp\"\n",
+        ),
+        (
+            "test/cpp/c.test.cpp",
+            "#define PROBLEM \"https://judge.yosupo.jp/problem/staticrmq\"\n#include \"../../src/cpp/a.hpp\"\n",
+        ),
+    ];
+    let dir = TempDir::with_repository("complete", &files);
+    let collection = collection(
+        dir.path_str(),
+        &["/src/cpp/a.hpp"],
+        &[
+            "/test/cpp/a.test.cpp",
+            "/test/cpp/b.test.cpp",
+            "/test/cpp/c.test.cpp",
+        ],
+    );
+    let articles = complete_articles(&collection).unwrap();
+
+    assert_eq!(articles.len(), 1);
+    assert_eq!(articles[0].commits.len(), 1);
+    assert_eq!(articles[0].tested_by.len(), 3);
+    // %verified first, then PROBLEM of a.test.cpp; b is IGNORE'd and c is a duplicate
+    assert_eq!(
+        articles[0].verified,
+        vec![
+            "https://judge.yosupo.jp/problem/staticrmq",
+            "https://judge.yosupo.jp/problem/unionfind",
+        ]
+    );
+    let problem_ids: Vec<&str> = articles[0]
+        .verified_entries
+        .iter()
+        .map(|entry| entry.problem_id.as_deref().unwrap())
+        .collect();
+    assert_eq!(problem_ids, vec!["staticrmq", "unionfind"]);
+}
+
+#[test]
+fn test_complete_articles_missing_replacement() {
+    let files = [
+        (
+            "src/cpp/a.hpp",
+            "// %=BEGIN DOC\n// %title\n// A\n// %deprecated\n// /src/cpp/b.hpp\n// %=END DOC\n// %=BEGIN CODE\nint a;\n// %=END CODE\n",
+        ),
+        (
+            "src/cpp/b.hpp",
+            "// %=BEGIN DOC\n// %title\n// B\n// %deprecated\n// /src/cpp/none.hpp\n// %=END DOC\n// %=BEGIN CODE\nint b;\n// %=END CODE\n",
+        ),
+    ];
+    let dir = TempDir::with_repository("replacement", &files);
+    let collection = collection(dir.path_str(), &["/src/cpp/a.hpp", "/src/cpp/b.hpp"], &[]);
+    let result = complete_articles(&collection);
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Replacement of deprecated article is not found: /src/cpp/b.hpp\n/src/cpp/none.hpp"
+    );
+}
+
+#[test]
+fn test_read_missing_source_file() {
+    let collection = collection("test", &["/src/cpp/none.hpp"], &[]);
+    let err = read_source_file(&collection, "/src/cpp/none.hpp").unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Io(_)));
+    assert!(err.to_string().contains("/src/cpp/none.hpp"));
+}
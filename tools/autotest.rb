@@ -10,6 +10,8 @@ option_parser = OptionParser.new do |opts|
   opts.on('--collection-path path') { |v| @collection_path = v }
   opts.on('--tempdir path') { |v| @tempdir = v }
   opts.on('--output-result-path path') { |v| @output_result_path = v }
+  # written by `gather --output-dir-example`
+  opts.on('--example-dir path') { |v| @example_dir = v }
 end
 option_parser.parse!(ARGV)
 
@@ -74,10 +76,28 @@ collection['source_sets'].each do |source_set|
     }
   end
 
+  example_paths = []
+  example_paths = Dir.glob(File.join(@example_dir, 'src', lang, '**', '*.example*')).sort if @example_dir
+  example_results = example_paths.map do |example_path|
+    @logger.info("run_test #{example_path}")
+    result = tester.run_test(example_path, @basedir, @tempdir)
+    {
+      'path' => example_path,
+      'type' => 'run_example',
+      'results' => result.map do |k, v|
+                     {
+                       'option' => k, 'ok' => v[:ok], 'timedout' => v[:timedout],
+                       'stdout' => v[:stdout], 'stderr' => v[:stderr]
+                     }
+                   end
+    }
+  end
+
   total_results << {
     'lang' => lang,
     'src_results' => src_results,
-    'test_results' => test_results
+    'test_results' => test_results,
+    'example_results' => example_results
   }
 end
 